@@ -0,0 +1,25 @@
+use std::env;
+
+/// Capture how libhdfs is linked for `hdrs::build_info()`.
+///
+/// This mirrors the lookup order of hdfs-sys:
+///
+/// - `vendored` feature enabled: compile and link in static.
+/// - `HDFS_LIB_DIR` or `HADOOP_HOME` specified: link in dynamic, or in static
+///   if `HDFS_STATIC` is set.
+/// - Otherwise: fallback to compile and link in static.
+fn main() {
+    for key in ["HDFS_LIB_DIR", "HADOOP_HOME", "HDFS_STATIC"] {
+        println!("cargo:rerun-if-env-changed={key}");
+    }
+
+    let vendored = env::var_os("CARGO_FEATURE_VENDORED").is_some();
+    let found = env::var_os("HDFS_LIB_DIR").is_some() || env::var_os("HADOOP_HOME").is_some();
+    let link_mode = if !vendored && found && env::var_os("HDFS_STATIC").is_none() {
+        "dynamic"
+    } else {
+        "static"
+    };
+
+    println!("cargo:rustc-env=HDRS_LINK_MODE={link_mode}");
+}
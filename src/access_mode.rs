@@ -0,0 +1,51 @@
+use std::ops::{BitOr, BitOrAssign};
+
+/// AccessMode is the bitflag of permissions checked by
+/// [`access`][crate::Client::access], like `R_OK`, `W_OK` and `X_OK` of
+/// POSIX `access(2)`.
+///
+/// # Examples
+///
+/// ```
+/// use hdrs::AccessMode;
+///
+/// let mode = AccessMode::READ | AccessMode::WRITE;
+/// assert!(mode.contains(AccessMode::READ));
+/// assert!(!mode.contains(AccessMode::EXECUTE));
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct AccessMode(u8);
+
+impl AccessMode {
+    /// Check for read permission.
+    pub const READ: AccessMode = AccessMode(0b100);
+    /// Check for write permission.
+    pub const WRITE: AccessMode = AccessMode(0b010);
+    /// Check for execute permission, which means list for dirs.
+    pub const EXECUTE: AccessMode = AccessMode(0b001);
+
+    /// Returns `true` if all bits of `other` are set in `self`.
+    pub fn contains(&self, other: AccessMode) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    /// Returns the bits in the same layout as unix permission, like `0o6`
+    /// for read and write.
+    pub fn bits(&self) -> u8 {
+        self.0
+    }
+}
+
+impl BitOr for AccessMode {
+    type Output = AccessMode;
+
+    fn bitor(self, rhs: AccessMode) -> AccessMode {
+        AccessMode(self.0 | rhs.0)
+    }
+}
+
+impl BitOrAssign for AccessMode {
+    fn bitor_assign(&mut self, rhs: AccessMode) {
+        self.0 |= rhs.0;
+    }
+}
@@ -7,7 +7,7 @@ use blocking::Unblock;
 use futures::lock::Mutex;
 use futures::{ready, AsyncSeek};
 
-use crate::File;
+use crate::{File, FlushMode, Metadata};
 
 /// A wrapper around `Arc<File>` that implements `Read`, `Write`, and `Seek`.
 struct ArcFile(Arc<File>);
@@ -37,11 +37,17 @@ impl Seek for ArcFile {
 /// Async version of file.
 ///
 /// Most code are inspired by [async-fs](https://github.com/smol-rs/async-fs).
+///
+/// # Flush
+///
+/// `flush` runs the blocking flush of [`File`] on a thread pool, so it honors
+/// the [`FlushMode`] set by
+/// [`OpenOptions::with_flush_mode`][crate::OpenOptions::with_flush_mode] the
+/// same as sync writers. The flush is skipped if nothing has been written
+/// since the last one.
 pub struct AsyncFile {
     /// Always accessible reference to the file.
-    ///
-    /// Not used for now, just save for future use.
-    _file: Arc<File>,
+    file: Arc<File>,
 
     /// Performs blocking I/O operations on a thread pool.
     unblock: Mutex<Unblock<ArcFile>>,
@@ -62,13 +68,26 @@ impl AsyncFile {
         let unblock = Mutex::new(Unblock::new(ArcFile(file.clone())));
         let read_pos = None;
         AsyncFile {
-            _file: file,
+            file,
             unblock,
             read_pos,
             is_dirty,
         }
     }
 
+    /// Stat the file to get its metadata.
+    ///
+    /// The blocking stat will be performed on a thread pool.
+    pub async fn metadata(&self) -> Result<Metadata> {
+        let file = self.file.clone();
+        blocking::unblock(move || file.metadata()).await
+    }
+
+    /// Returns the [`FlushMode`] used by `flush` on this file.
+    pub fn flush_mode(&self) -> FlushMode {
+        self.file.flush_mode()
+    }
+
     /// Repositions the cursor after reading.
     ///
     /// When reading from a file, actual file reads run asynchronously in the background, which
@@ -132,6 +151,8 @@ impl futures::AsyncWrite for AsyncFile {
     }
 
     fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<()>> {
+        // `Unblock` flushes its buffer and then calls `File::flush`, which
+        // dispatches on the configured flush mode.
         if self.is_dirty {
             ready!(Pin::new(self.unblock.get_mut()).poll_flush(cx))?;
             self.is_dirty = false;
@@ -227,6 +248,9 @@ mod tests {
             .await
             .expect("open file success");
 
+        let meta = f.metadata().await.expect("stat must succeed");
+        assert_eq!(meta.len(), 13);
+
         let _ = f.seek(SeekFrom::Start(0)).await.expect("seek must success");
         let mut s = String::new();
         let n = f.read_to_string(&mut s).await.expect("read must succeed");
@@ -0,0 +1,84 @@
+use std::fmt;
+
+/// LinkMode is how libhdfs is linked into the binary.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LinkMode {
+    /// libhdfs is compiled and linked in static.
+    Static,
+    /// libhdfs is loaded from `HDFS_LIB_DIR` or `HADOOP_HOME` at runtime.
+    Dynamic,
+}
+
+impl fmt::Display for LinkMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LinkMode::Static => write!(f, "static"),
+            LinkMode::Dynamic => write!(f, "dynamic"),
+        }
+    }
+}
+
+/// BuildInfo describes how hdrs is built, returned by [`build_info`].
+///
+/// # Notes
+///
+/// The libhdfs binding (for example `hdfs_3_3`) is selected by hdfs-sys's
+/// features which could be enabled by any crate in the dependency graph, so
+/// it's not visible to hdrs and not included here. Please run
+/// `cargo tree -e features -i hdfs-sys` to find it out.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BuildInfo {
+    /// Version of hdrs, like `0.3.2`.
+    pub version: &'static str,
+    /// Whether the `vendored` feature is enabled.
+    pub vendored: bool,
+    /// How libhdfs is linked.
+    pub link_mode: LinkMode,
+}
+
+impl fmt::Display for BuildInfo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "hdrs {} (libhdfs: {}", self.version, self.link_mode)?;
+        if self.vendored {
+            write!(f, ", vendored")?;
+        }
+        write!(f, ")")
+    }
+}
+
+/// Returns the build information of hdrs, useful for `--version` output
+/// and bug reports.
+///
+/// # Examples
+///
+/// ```no_run
+/// println!("{}", hdrs::build_info());
+/// ```
+pub fn build_info() -> BuildInfo {
+    BuildInfo {
+        version: env!("CARGO_PKG_VERSION"),
+        vendored: cfg!(feature = "vendored"),
+        link_mode: match env!("HDRS_LINK_MODE") {
+            "dynamic" => LinkMode::Dynamic,
+            _ => LinkMode::Static,
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_build_info() {
+        let info = build_info();
+        assert_eq!(info.version, env!("CARGO_PKG_VERSION"));
+
+        let info = BuildInfo {
+            version: "0.3.2",
+            vendored: true,
+            link_mode: LinkMode::Static,
+        };
+        assert_eq!(info.to_string(), "hdrs 0.3.2 (libhdfs: static, vendored)");
+    }
+}
@@ -1,13 +1,29 @@
-use std::ffi::CString;
-use std::io;
+use std::collections::HashMap;
+use std::ffi::{CStr, CString};
+use std::io::Write;
 use std::mem::MaybeUninit;
+#[cfg(feature = "async_file")]
+use std::num::NonZeroUsize;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use std::{fs, io, ptr};
 
 use errno::{set_errno, Errno};
+#[cfg(feature = "async_file")]
+use futures::{channel::mpsc::Sender, stream, SinkExt, Stream, StreamExt};
 use hdfs_sys::*;
 use log::debug;
 
-use crate::metadata::Metadata;
-use crate::{OpenOptions, Readdir};
+use crate::config;
+use crate::krb5;
+use crate::metadata::{self, Metadata};
+use crate::{
+    normalize_path, AccessMode, DatanodeInfo, DirDiff, DirEntry, EntryFilter, FileKind, FsStats,
+    OpenOptions, RangeReader, Readdir, RenameOptions, SortKey,
+};
 
 /// Client holds the underlying connection to hdfs clusters.
 ///
@@ -22,8 +38,34 @@ use crate::{OpenOptions, Readdir};
 /// So it's better for us to not call `hdfsDisconnect` manually.
 /// Aka, don't implement `Drop` to disconnect the connection.
 ///
+/// The only exception is clients connected with
+/// [`ClientBuilder::with_force_new_instance`]: the filesystem instance is not
+/// cached and owned by this client, so it will be disconnected while `Drop`.
+///
 /// Reference: [IOException: Filesystem closed exception when running oozie workflo](https://stackoverflow.com/questions/23779186/ioexception-filesystem-closed-exception-when-running-oozie-workflow)
 ///
+/// # Unsupported operations
+///
+/// libhdfs only exposes a subset of hdfs RPCs. Following operations are
+/// provided for API completeness but always return
+/// [`Unsupported`][io::ErrorKind::Unsupported], please use the hdfs cli
+/// instead:
+///
+/// | Operation | Alternative |
+/// | --- | --- |
+/// | [`Client::set_erasure_coding_policy`] | `hdfs ec -setPolicy -path <path> -policy <policy>` |
+/// | [`Client::get_erasure_coding_policy`] | `hdfs ec -getPolicy -path <path>` |
+/// | [`Client::unset_erasure_coding_policy`] | `hdfs ec -unsetPolicy -path <path>` |
+/// | [`Client::create_snapshot`] | `hdfs dfs -createSnapshot <path> [<name>]` |
+/// | [`Client::delete_snapshot`] | `hdfs dfs -deleteSnapshot <path> <name>` |
+/// | [`Client::rename_snapshot`] | `hdfs dfs -renameSnapshot <path> <old_name> <new_name>` |
+/// | [`Client::resolve_path`] | none, symlinks are disabled by default |
+/// | [`Client::recover_lease`] | `hdfs debug recoverLease -path <path>` |
+/// | [`Client::is_file_closed`] | none |
+/// | [`Client::access`] | [`Client::can_write`] for write permission of a dir |
+/// | [`Client::datanode_report`] | `hdfs dfsadmin -report` |
+/// | [`Client::storage_policies`] | `hdfs storagepolicies -listPolicies` |
+///
 /// # Examples
 ///
 /// ```no_run
@@ -37,6 +79,15 @@ use crate::{OpenOptions, Readdir};
 #[derive(Debug)]
 pub struct Client {
     fs: hdfsFS,
+    /// The default buffer size used by [`Client::open_file`].
+    default_buffer_size: usize,
+    /// The filesystem instance owned by this client, it will be disconnected
+    /// once all clients sharing it are dropped.
+    owned: Option<Arc<OwnedInstance>>,
+    /// The timeout of namespace operations, see [`Client::with_operation_timeout`].
+    operation_timeout: Option<Duration>,
+    /// The user set by [`ClientBuilder::with_user`].
+    user: Option<String>,
 }
 
 /// The builder of connecting to hdfs clusters.
@@ -55,6 +106,10 @@ pub struct ClientBuilder {
     name_node: String,
     user: Option<String>,
     kerberos_ticket_cache_path: Option<String>,
+    configs: Vec<(String, String)>,
+    default_buffer_size: usize,
+    fallback_name_nodes: Vec<String>,
+    force_new_instance: bool,
 }
 
 impl ClientBuilder {
@@ -87,6 +142,10 @@ impl ClientBuilder {
             name_node: name_node.to_string(),
             user: None,
             kerberos_ticket_cache_path: None,
+            configs: Vec::new(),
+            default_buffer_size: 0,
+            fallback_name_nodes: Vec::new(),
+            force_new_instance: false,
         }
     }
 
@@ -106,6 +165,10 @@ impl ClientBuilder {
 
     /// Set the krb5 ticket cache path for existing ClientBuilder
     ///
+    /// The ticket cache will be checked by [`ClientBuilder::connect`]: an
+    /// error will be returned if the cache file doesn't exist or is not
+    /// readable. Non-file caches like `KEYRING:` are not checked.
+    ///
     /// # Examples
     ///
     /// ```no_run
@@ -123,6 +186,235 @@ impl ClientBuilder {
         self
     }
 
+    /// Set a configuration property for existing ClientBuilder
+    ///
+    /// The property will override the one loaded from XML configuration files.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use hdrs::{Client, ClientBuilder};
+    ///
+    /// let client = ClientBuilder::new("default")
+    ///     .with_config("dfs.client.use.datanode.hostname", "true")
+    ///     .connect();
+    /// ```
+    pub fn with_config(mut self, key: &str, value: &str) -> ClientBuilder {
+        self.configs.push((key.to_string(), value.to_string()));
+        self
+    }
+
+    /// Load all properties from a hadoop XML configuration file like `hdfs-site.xml`
+    ///
+    /// Properties are applied in the same way as [`ClientBuilder::with_config`], so it's
+    /// possible to connect to multiple clusters from one process.
+    ///
+    /// Returns an [`io::Result`] if the file can't be read or is not a valid configuration.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use hdrs::{Client, ClientBuilder};
+    ///
+    /// let client = ClientBuilder::new("default")
+    ///     .with_config_file("/etc/hadoop/cluster-a/hdfs-site.xml")
+    ///     .expect("config file must be valid")
+    ///     .connect();
+    /// ```
+    pub fn with_config_file(mut self, path: &str) -> io::Result<ClientBuilder> {
+        let content = fs::read_to_string(path)?;
+        self.configs.extend(config::parse_xml(&content)?);
+        Ok(self)
+    }
+
+    /// Set the default buffer size of files opened by [`Client::open_file`]
+    ///
+    /// [`OpenOptions::with_buffer_size`] still overrides it per open.
+    ///
+    /// `0` by default, which means using the configured values of hdfs.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use hdrs::{Client, ClientBuilder};
+    ///
+    /// let client = ClientBuilder::new("default")
+    ///     .with_default_buffer_size(4 * 1024 * 1024)
+    ///     .connect();
+    /// ```
+    pub fn with_default_buffer_size(mut self, buffer_size: usize) -> ClientBuilder {
+        self.default_buffer_size = buffer_size;
+        self
+    }
+
+    /// Set the read-ahead size in bytes for files opened by this client.
+    ///
+    /// libhdfs doesn't expose `FSDataInputStream.setReadahead` per file, so
+    /// this sets `dfs.client.cache.readahead` for the whole client instead,
+    /// which makes datanodes read ahead for sequential scans.
+    ///
+    /// # Notes
+    ///
+    /// Hadoop caches filesystem instances by name node and user, and the
+    /// configuration only applies while the instance is created. Use with
+    /// [`ClientBuilder::with_force_new_instance`] if another client to the
+    /// same cluster may have been connected in this process.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use hdrs::{Client, ClientBuilder};
+    ///
+    /// let client = ClientBuilder::new("default")
+    ///     .with_read_ahead(4 * 1024 * 1024)
+    ///     .connect();
+    /// ```
+    pub fn with_read_ahead(self, bytes: u64) -> ClientBuilder {
+        self.with_config("dfs.client.cache.readahead", &bytes.to_string())
+    }
+
+    /// Set the socket timeout of reading from and writing to datanodes.
+    ///
+    /// This sets following keys in milliseconds, so I/O fails promptly
+    /// instead of hanging on a wedged datanode:
+    ///
+    /// - `dfs.client.socket-timeout`: read timeout, `60s` by default.
+    /// - `dfs.datanode.socket.write.timeout`: write timeout, `480s` by default.
+    ///
+    /// [`Duration::ZERO`] disables the timeouts.
+    ///
+    /// Connecting to name nodes is not affected. The same as
+    /// [`ClientBuilder::with_read_ahead`], this only applies to newly created
+    /// filesystem instances.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use std::time::Duration;
+    ///
+    /// use hdrs::{Client, ClientBuilder};
+    ///
+    /// let client = ClientBuilder::new("default")
+    ///     .with_datanode_socket_timeout(Duration::from_secs(10))
+    ///     .connect();
+    /// ```
+    pub fn with_datanode_socket_timeout(self, timeout: Duration) -> ClientBuilder {
+        let millis = timeout.as_millis().to_string();
+        self.with_config("dfs.client.socket-timeout", &millis)
+            .with_config("dfs.datanode.socket.write.timeout", &millis)
+    }
+
+    /// Set whether to verify block checksums for short-circuit local reads.
+    ///
+    /// This sets `dfs.client.read.shortcircuit.skip.checksum` to `!verify`.
+    /// Disabling verification saves CPU on trusted hardware, but corrupted
+    /// blocks on local disks will be returned silently instead of being
+    /// detected and read from other replicas.
+    ///
+    /// `true` by default, the same as hdfs.
+    ///
+    /// # Notes
+    ///
+    /// Remote reads via datanodes are always verified: hadoop only allows
+    /// to disable it by `FileSystem.setVerifyChecksum`, which is not exposed
+    /// by libhdfs. The same as [`ClientBuilder::with_read_ahead`], this only
+    /// applies to newly created filesystem instances.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use hdrs::{Client, ClientBuilder};
+    ///
+    /// let client = ClientBuilder::new("default")
+    ///     .with_verify_checksum(false)
+    ///     .connect();
+    /// ```
+    pub fn with_verify_checksum(self, verify: bool) -> ClientBuilder {
+        self.with_config(
+            "dfs.client.read.shortcircuit.skip.checksum",
+            &(!verify).to_string(),
+        )
+    }
+
+    /// Enable or disable short-circuit local reads.
+    ///
+    /// Short-circuit reads let clients co-located with datanodes read block
+    /// files directly instead of going through the datanode's TCP stack,
+    /// which is a big win for local reads. This sets
+    /// `dfs.client.read.shortcircuit`.
+    ///
+    /// # Notes
+    ///
+    /// - Datanodes must enable short-circuit reads too, and the client's
+    ///   `dfs.domain.socket.path` must match the datanodes'. It's usually
+    ///   loaded from `hdfs-site.xml`, or could be set by
+    ///   [`ClientBuilder::with_config`]. hdfs falls back to remote reads
+    ///   silently if the socket is not available.
+    /// - libhdfs doesn't provide per-open read options, so this applies to all
+    ///   files opened by the client. The same as
+    ///   [`ClientBuilder::with_read_ahead`], this only applies to newly
+    ///   created filesystem instances.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use hdrs::{Client, ClientBuilder};
+    ///
+    /// let client = ClientBuilder::new("default")
+    ///     .with_short_circuit(true)
+    ///     .with_config("dfs.domain.socket.path", "/var/lib/hadoop-hdfs/dn_socket")
+    ///     .connect();
+    /// ```
+    pub fn with_short_circuit(self, enabled: bool) -> ClientBuilder {
+        self.with_config("dfs.client.read.shortcircuit", &enabled.to_string())
+    }
+
+    /// Set fallback name nodes which will be tried in order if previous ones failed
+    ///
+    /// Every name node will be probed by stating `/` since hdfs connects lazily.
+    /// If all of them failed, errors of all name nodes will be returned.
+    ///
+    /// # Notes
+    ///
+    /// HA configuration via `dfs.nameservices` is preferable since hdfs will
+    /// fail over automatically during I/O. This only helps at connect time in
+    /// environments where HA configuration is not available.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use hdrs::{Client, ClientBuilder};
+    ///
+    /// let client = ClientBuilder::new("hdfs://nn1:8020")
+    ///     .with_fallback_name_nodes(vec!["hdfs://nn2:8020".to_string()])
+    ///     .connect();
+    /// ```
+    pub fn with_fallback_name_nodes(mut self, name_nodes: Vec<String>) -> ClientBuilder {
+        self.fallback_name_nodes = name_nodes;
+        self
+    }
+
+    /// Force to create a new filesystem instance instead of reusing the one
+    /// cached by hadoop.
+    ///
+    /// The new instance is owned by the connected [`Client`] and will be
+    /// disconnected while `Drop` to reclaim its resources, so files and
+    /// entries opened from the client must not outlive it.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use hdrs::{Client, ClientBuilder};
+    ///
+    /// let client = ClientBuilder::new("file:///")
+    ///     .with_force_new_instance(true)
+    ///     .connect();
+    /// ```
+    pub fn with_force_new_instance(mut self, force_new_instance: bool) -> ClientBuilder {
+        self.force_new_instance = force_new_instance;
+        self
+    }
+
     /// Connect for existing ClientBuilder to get a hdfs client
     ///
     /// Returns an [`io::Result`] if any error happens.
@@ -135,28 +427,81 @@ impl ClientBuilder {
     /// let mut client = ClientBuilder::new("default").connect();
     /// ```
     pub fn connect(self) -> io::Result<Client> {
+        if let Some(path) = &self.kerberos_ticket_cache_path {
+            match krb5::check_ticket_cache(path)? {
+                Some(principal) => debug!("use kerberos principal {} from {}", principal, path),
+                None => debug!("use kerberos ticket cache {}", path),
+            }
+        }
+
+        if self.fallback_name_nodes.is_empty() {
+            return self.connect_name_node(&self.name_node);
+        }
+
+        let mut errors = Vec::new();
+        for name_node in std::iter::once(&self.name_node).chain(&self.fallback_name_nodes) {
+            // hdfs connects lazily, so we need to probe the name node.
+            match self
+                .connect_name_node(name_node)
+                .and_then(|client| client.metadata("/").map(|_| client))
+            {
+                Ok(client) => return Ok(client),
+                Err(e) => {
+                    debug!("connect name node {} failed: {}", name_node, e);
+                    errors.push((name_node, e))
+                }
+            }
+        }
+
+        let kind = errors
+            .last()
+            .map(|(_, e)| e.kind())
+            .unwrap_or(io::ErrorKind::Other);
+        let msg = errors
+            .iter()
+            .map(|(name_node, e)| format!("{name_node}: {e}"))
+            .collect::<Vec<_>>()
+            .join("; ");
+        Err(io::Error::new(
+            kind,
+            format!("all name nodes failed: {msg}"),
+        ))
+    }
+
+    fn connect_name_node(&self, name_node: &str) -> io::Result<Client> {
         set_errno(Errno(0));
 
-        debug!("connect name node {}", &self.name_node);
+        debug!("connect name node {}", name_node);
+
+        // hdfsBuilderConfSetStr doesn't copy key and value, so we must
+        // keep them alive until connected.
+        let mut configs = Vec::with_capacity(self.configs.len());
+        for (k, v) in &self.configs {
+            configs.push((CString::new(k.as_str())?, CString::new(v.as_str())?));
+        }
 
         let fs = {
             let builder = unsafe { hdfsNewBuilder() };
 
-            let name_node = CString::new(self.name_node.as_bytes())?;
+            let name_node = CString::new(name_node.as_bytes())?;
             let mut user = MaybeUninit::uninit();
             let mut ticket_cache_path = MaybeUninit::uninit();
 
             unsafe { hdfsBuilderSetNameNode(builder, name_node.as_ptr()) };
 
-            if let Some(v) = self.user {
-                user.write(CString::new(v)?);
+            if self.force_new_instance {
+                unsafe { hdfsBuilderSetForceNewInstance(builder) };
+            }
+
+            if let Some(v) = &self.user {
+                user.write(CString::new(v.as_str())?);
                 unsafe {
                     hdfsBuilderSetUserName(builder, user.assume_init_ref().as_ptr());
                 }
             }
 
-            if let Some(v) = self.kerberos_ticket_cache_path {
-                ticket_cache_path.write(CString::new(v)?);
+            if let Some(v) = &self.kerberos_ticket_cache_path {
+                ticket_cache_path.write(CString::new(v.as_str())?);
                 unsafe {
                     hdfsBuilderSetKerbTicketCachePath(
                         builder,
@@ -165,6 +510,15 @@ impl ClientBuilder {
                 }
             }
 
+            for (k, v) in &configs {
+                let n = unsafe { hdfsBuilderConfSetStr(builder, k.as_ptr(), v.as_ptr()) };
+                // hdfsBuilderConfSetStr returns errno directly.
+                if n != 0 {
+                    unsafe { hdfsFreeBuilder(builder) };
+                    return Err(io::Error::from_raw_os_error(n));
+                }
+            }
+
             unsafe { hdfsBuilderConnect(builder) }
         };
 
@@ -172,22 +526,147 @@ impl ClientBuilder {
             return Err(io::Error::last_os_error());
         }
 
-        debug!("name node {} connected", self.name_node);
-        Ok(Client::new(fs))
+        debug!("name node {} connected", name_node);
+        let mut client = Client::new(fs);
+        client.default_buffer_size = self.default_buffer_size;
+        client.user = self.user.clone();
+        if self.force_new_instance {
+            client.owned = Some(Arc::new(OwnedInstance(fs)));
+        }
+        Ok(client)
+    }
+}
+
+/// OwnedInstance disconnects the filesystem instance owned by clients while
+/// `Drop`, see docs of `Client`.
+#[derive(Debug)]
+struct OwnedInstance(hdfsFS);
+
+impl Drop for OwnedInstance {
+    fn drop(&mut self) {
+        debug!("disconnect owned filesystem instance");
+        let n = unsafe { hdfsDisconnect(self.0) };
+        if n == -1 {
+            debug!(
+                "disconnect owned filesystem instance failed: {}",
+                io::Error::last_os_error()
+            );
+        }
     }
 }
 
+/// HDFS's client handle is thread safe.
+unsafe impl Send for OwnedInstance {}
+unsafe impl Sync for OwnedInstance {}
+
 /// HDFS's client handle is thread safe.
 unsafe impl Send for Client {}
 unsafe impl Sync for Client {}
 
 impl Client {
     pub(crate) fn new(fs: hdfsFS) -> Self {
-        Self { fs }
+        Self {
+            fs,
+            default_buffer_size: 0,
+            owned: None,
+            operation_timeout: None,
+            user: None,
+        }
+    }
+
+    /// Create a client sharing the same filesystem instance but without
+    /// timeout.
+    ///
+    /// An owned instance stays connected until all clients sharing it are
+    /// dropped, so the returned client can be moved into tasks that outlive
+    /// `self`.
+    pub(crate) fn share(&self) -> Client {
+        Client {
+            fs: self.fs,
+            default_buffer_size: self.default_buffer_size,
+            owned: self.owned.clone(),
+            operation_timeout: None,
+            user: self.user.clone(),
+        }
+    }
+
+    /// Set the timeout of namespace operations for this client.
+    ///
+    /// Following operations will run on a new worker thread and return
+    /// [`TimedOut`][io::ErrorKind::TimedOut] if not finished in time:
+    ///
+    /// - [`Client::metadata`]
+    /// - [`Client::read_dir`]
+    /// - [`Client::create_dir`]
+    /// - [`Client::remove_file`]
+    /// - [`Client::remove_dir`]
+    /// - [`Client::remove_dir_all`]
+    /// - [`Client::rename_file`]
+    ///
+    /// Other operations call them internally without timeout, and file I/O is
+    /// not covered.
+    ///
+    /// # Notes
+    ///
+    /// JNI calls can't be cancelled, so the timed out operation is abandoned
+    /// instead: its worker thread keeps running until the call returns, or
+    /// leaks forever if the call hangs. The operation may still take effect
+    /// after timeout, for example, a timed out `remove_file` could remove
+    /// the file later. With [`ClientBuilder::with_force_new_instance`], the
+    /// instance will not be disconnected until abandoned calls return.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use std::time::Duration;
+    ///
+    /// use hdrs::{Client, ClientBuilder};
+    ///
+    /// let fs = ClientBuilder::new("default")
+    ///     .with_user("default")
+    ///     .connect()
+    ///     .expect("client connect succeed")
+    ///     .with_operation_timeout(Duration::from_secs(10));
+    /// let fi = fs.metadata("/tmp/hello.txt");
+    /// ```
+    pub fn with_operation_timeout(mut self, timeout: Duration) -> Client {
+        self.operation_timeout = Some(timeout);
+        self
+    }
+
+    /// Run `f` on a worker thread with a client sharing the same filesystem
+    /// but without timeout, returns `TimedOut` if it's not finished in time.
+    fn run_with_timeout<T: Send + 'static>(
+        &self,
+        timeout: Duration,
+        f: impl FnOnce(&Client) -> io::Result<T> + Send + 'static,
+    ) -> io::Result<T> {
+        let client = self.share();
+
+        let (tx, rx) = mpsc::channel();
+        thread::Builder::new()
+            .name("hdrs-operation".to_string())
+            .spawn(move || {
+                // The receiver could be dropped while timed out.
+                let _ = tx.send(f(&client));
+            })?;
+
+        match rx.recv_timeout(timeout) {
+            Ok(res) => res,
+            Err(RecvTimeoutError::Timeout) => Err(io::Error::new(
+                io::ErrorKind::TimedOut,
+                format!("operation timed out after {timeout:?}"),
+            )),
+            Err(RecvTimeoutError::Disconnected) => {
+                Err(io::Error::other("operation worker panicked"))
+            }
+        }
     }
 
     /// Open will create a stream builder for later IO operations.
     ///
+    /// The buffer size defaults to [`ClientBuilder::with_default_buffer_size`].
+    ///
     /// # Examples
     ///
     /// ```no_run
@@ -200,7 +679,42 @@ impl Client {
     /// let open_options = fs.open_file();
     /// ```
     pub fn open_file(&self) -> OpenOptions {
-        OpenOptions::new(self.fs)
+        let mut opt = OpenOptions::new(self.fs);
+        opt.with_buffer_size(self.default_buffer_size);
+        opt
+    }
+
+    /// Open a read view of the first `len` bytes of a file.
+    ///
+    /// Reads stop at `len` even if another writer keeps appending to the file,
+    /// so consumers that checkpoint at a length get reproducible reads of
+    /// `[0, len)`. The view is backed by positional reads, which fetch the
+    /// latest visible length of blocks being written from datanodes.
+    ///
+    /// `len` is not validated against the current length of file: the length
+    /// reported by namenode lags behind for files being written. Reads return
+    /// EOF early if the file is shorter than `len`.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use std::io::Read;
+    ///
+    /// use hdrs::{Client, ClientBuilder};
+    ///
+    /// let fs = ClientBuilder::new("default")
+    ///     .with_user("default")
+    ///     .connect()
+    ///     .expect("client connect succeed");
+    /// let mut buf = Vec::new();
+    /// fs.open_at_length("/tmp/events.log", 1024)
+    ///     .expect("must open success")
+    ///     .read_to_end(&mut buf)
+    ///     .expect("must read success");
+    /// ```
+    pub fn open_at_length(&self, path: &str, len: u64) -> io::Result<RangeReader<'static>> {
+        let f = self.open_file().read(true).open(path)?;
+        Ok(RangeReader::owned(f, 0, len))
     }
 
     /// Delete a file.
@@ -217,6 +731,11 @@ impl Client {
     /// let _ = fs.remove_file("/tmp/hello.txt");
     /// ```
     pub fn remove_file(&self, path: &str) -> io::Result<()> {
+        if let Some(timeout) = self.operation_timeout {
+            let path = path.to_string();
+            return self.run_with_timeout(timeout, move |fs| fs.remove_file(&path));
+        }
+
         debug!("remove file {}", path);
 
         let n = unsafe {
@@ -232,10 +751,50 @@ impl Client {
         Ok(())
     }
 
+    /// Delete a file if it exists.
+    ///
+    /// Returns `true` if the file has been removed, `false` if it didn't
+    /// exist. Other errors like permission denied are still returned.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use hdrs::{Client, ClientBuilder};
+    ///
+    /// let fs = ClientBuilder::new("default")
+    ///     .with_user("default")
+    ///     .connect()
+    ///     .expect("client connect succeed");
+    /// let removed = fs.remove_file_if_exists("/tmp/hello.txt");
+    /// ```
+    pub fn remove_file_if_exists(&self, path: &str) -> io::Result<bool> {
+        let res = self.remove_file(path);
+        self.removed_or_absent(path, res)
+    }
+
     /// Rename a file.
     ///
     /// **ATTENTION**: the destination directory must exist.
     ///
+    /// Both paths will be normalized by [`normalize_path`] first, so `/a/b`
+    /// and `/a/b/` are treated as the same path.
+    ///
+    /// If `old_path` is a symlink, the link itself will be renamed instead of its target.
+    ///
+    /// # Errors
+    ///
+    /// Both paths are stated before renaming to report type mismatches clearly,
+    /// instead of hdfs's confusing behavior like moving a file into an existing
+    /// dir. The check is not atomic with the rename itself.
+    ///
+    /// - [`IsADirectory`][io::ErrorKind::IsADirectory]: `old_path` is a file
+    ///   while `new_path` is an existing dir.
+    /// - [`NotADirectory`][io::ErrorKind::NotADirectory]: `old_path` is a dir
+    ///   while `new_path` is an existing file.
+    /// - [`AlreadyExists`][io::ErrorKind::AlreadyExists]: `new_path` exists
+    ///   with the same kind as `old_path`. Use [`Client::rename_file_overwrite`]
+    ///   to replace an existing file.
+    ///
     /// # Examples
     ///
     /// ```no_run
@@ -248,23 +807,62 @@ impl Client {
     /// let _ = fs.rename_file("/tmp/hello.txt._COPY_", "/tmp/hello.txt");
     /// ```
     pub fn rename_file(&self, old_path: &str, new_path: &str) -> io::Result<()> {
+        if let Some(timeout) = self.operation_timeout {
+            let (old_path, new_path) = (old_path.to_string(), new_path.to_string());
+            return self.run_with_timeout(timeout, move |fs| fs.rename_file(&old_path, &new_path));
+        }
+
         debug!("rename file {} -> {}", old_path, new_path);
 
+        self.check_rename_kind(old_path, new_path)?;
+
         let n = {
-            let old_path = CString::new(old_path)?;
-            let new_path = CString::new(new_path)?;
+            let old_path = CString::new(normalize_path(old_path))?;
+            let new_path = CString::new(normalize_path(new_path))?;
             unsafe { hdfsRename(self.fs, old_path.as_ptr(), new_path.as_ptr()) }
         };
 
         if n == -1 {
-            return Err(io::Error::last_os_error());
+            let err = io::Error::last_os_error();
+            // hdfs reports an existing destination as a generic error, the
+            // destination could be created after our check.
+            if self.metadata(new_path).is_ok() {
+                return Err(already_exists(new_path));
+            }
+            return Err(err);
         }
 
         debug!("rename file {} -> {} finished", old_path, new_path);
         Ok(())
     }
 
-    /// Delete a dir.
+    /// Check that an existing destination has the same kind as the source.
+    fn check_rename_kind(&self, old_path: &str, new_path: &str) -> io::Result<()> {
+        let dst = match self.metadata(new_path) {
+            Ok(meta) => meta,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(()),
+            Err(e) => return Err(e),
+        };
+        let src = self.metadata(old_path)?;
+
+        match (src.is_dir(), dst.is_dir()) {
+            (false, true) => Err(io::Error::new(
+                io::ErrorKind::IsADirectory,
+                format!("cannot rename file {old_path} to existing dir {new_path}"),
+            )),
+            (true, false) => Err(io::Error::new(
+                io::ErrorKind::NotADirectory,
+                format!("cannot rename dir {old_path} to existing file {new_path}"),
+            )),
+            // hdfs moves `old_path` into `new_path` if it's an existing dir,
+            // reject it the same as files for consistency.
+            _ => Err(already_exists(new_path)),
+        }
+    }
+
+    /// Rename will create a builder to configure how a file is renamed.
+    ///
+    /// Use [`Client::rename_file`] for the simple rename.
     ///
     /// # Examples
     ///
@@ -275,25 +873,30 @@ impl Client {
     ///     .with_user("default")
     ///     .connect()
     ///     .expect("client connect succeed");
-    /// let _ = fs.remove_dir("/tmp/xxx");
+    /// let _ = fs
+    ///     .rename("/tmp/hello.txt._COPY_", "/tmp/hello.txt")
+    ///     .overwrite(true)
+    ///     .run();
     /// ```
-    pub fn remove_dir(&self, path: &str) -> io::Result<()> {
-        debug!("remove dir {}", path);
-
-        let n = unsafe {
-            let p = CString::new(path)?;
-            hdfsDelete(self.fs, p.as_ptr(), false.into())
-        };
-
-        if n == -1 {
-            return Err(io::Error::last_os_error());
-        }
-
-        debug!("delete dir {} finished", path);
-        Ok(())
+    pub fn rename(&self, old_path: &str, new_path: &str) -> RenameOptions {
+        RenameOptions::new(self.fs, old_path, new_path)
     }
 
-    /// Delete a dir recursively.
+    /// Rename a file and overwrite the destination if it exists.
+    ///
+    /// Returns the metadata of the overwritten destination, or `None` if
+    /// the destination didn't exist before.
+    ///
+    /// The existing destination is renamed aside to `new_path._overwritten_<suffix>`
+    /// first, and removed only after the source has been renamed in. If the
+    /// rename fails, the destination is restored. Only files can be
+    /// overwritten, an existing dir destination is rejected with
+    /// [`IsADirectory`][io::ErrorKind::IsADirectory] before anything is
+    /// touched.
+    ///
+    /// **ATTENTION**: the destination directory must exist, and the
+    /// operation is not atomic: readers can see the destination missing
+    /// between the two renames.
     ///
     /// # Examples
     ///
@@ -304,29 +907,1322 @@ impl Client {
     ///     .with_user("default")
     ///     .connect()
     ///     .expect("client connect succeed");
-    /// let _ = fs.remove_dir_all("/tmp/xxx/");
+    /// let replaced = fs.rename_file_overwrite("/tmp/hello.txt._COPY_", "/tmp/hello.txt");
+    /// ```
+    pub fn rename_file_overwrite(
+        &self,
+        old_path: &str,
+        new_path: &str,
+    ) -> io::Result<Option<Metadata>> {
+        // Fail with `NotFound` before touching the destination.
+        let src = self.metadata(old_path)?;
+        let replaced = match self.metadata(new_path) {
+            Ok(meta) => meta,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => {
+                self.rename_file(old_path, new_path)?;
+                return Ok(None);
+            }
+            Err(e) => return Err(e),
+        };
+        if replaced.is_dir() {
+            return Err(io::Error::new(
+                io::ErrorKind::IsADirectory,
+                format!("cannot overwrite existing dir {new_path}"),
+            ));
+        }
+        if src.is_dir() {
+            return Err(io::Error::new(
+                io::ErrorKind::NotADirectory,
+                format!("cannot rename dir {old_path} to existing file {new_path}"),
+            ));
+        }
+
+        let aside = format!("{new_path}._overwritten_{}", tmp_suffix());
+        self.rename_file(new_path, &aside)?;
+
+        if let Err(e) = self.rename_file(old_path, new_path) {
+            return match self.rename_file(&aside, new_path) {
+                Ok(()) => Err(e),
+                Err(restore) => Err(io::Error::new(
+                    e.kind(),
+                    format!(
+                        "rename {old_path} to {new_path} failed: {e}, and restore the overwritten file from {aside} failed: {restore}"
+                    ),
+                )),
+            };
+        }
+
+        if let Err(e) = self.remove_file(&aside) {
+            debug!("remove overwritten file {} failed: {}", aside, e);
+        }
+        Ok(Some(replaced))
+    }
+
+    /// Delete a dir.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use hdrs::{Client, ClientBuilder};
+    ///
+    /// let fs = ClientBuilder::new("default")
+    ///     .with_user("default")
+    ///     .connect()
+    ///     .expect("client connect succeed");
+    /// let _ = fs.remove_dir("/tmp/xxx");
+    /// ```
+    pub fn remove_dir(&self, path: &str) -> io::Result<()> {
+        if let Some(timeout) = self.operation_timeout {
+            let path = path.to_string();
+            return self.run_with_timeout(timeout, move |fs| fs.remove_dir(&path));
+        }
+
+        debug!("remove dir {}", path);
+
+        let n = unsafe {
+            let p = CString::new(path)?;
+            hdfsDelete(self.fs, p.as_ptr(), false.into())
+        };
+
+        if n == -1 {
+            return Err(io::Error::last_os_error());
+        }
+
+        debug!("delete dir {} finished", path);
+        Ok(())
+    }
+
+    /// Delete a dir recursively.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use hdrs::{Client, ClientBuilder};
+    ///
+    /// let fs = ClientBuilder::new("default")
+    ///     .with_user("default")
+    ///     .connect()
+    ///     .expect("client connect succeed");
+    /// let _ = fs.remove_dir_all("/tmp/xxx/");
     /// ```
     pub fn remove_dir_all(&self, path: &str) -> io::Result<()> {
+        if let Some(timeout) = self.operation_timeout {
+            let path = path.to_string();
+            return self.run_with_timeout(timeout, move |fs| fs.remove_dir_all(&path));
+        }
+
         debug!("remove dir all {}", path);
 
-        let n = unsafe {
-            let p = CString::new(path)?;
-            hdfsDelete(self.fs, p.as_ptr(), true.into())
-        };
+        let n = unsafe {
+            let p = CString::new(path)?;
+            hdfsDelete(self.fs, p.as_ptr(), true.into())
+        };
+
+        if n == -1 {
+            return Err(io::Error::last_os_error());
+        }
+
+        debug!("delete dir all {} finished", path);
+        Ok(())
+    }
+
+    /// Delete a dir recursively if it exists.
+    ///
+    /// Returns `true` if the dir has been removed, `false` if it didn't
+    /// exist. Other errors like permission denied are still returned.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use hdrs::{Client, ClientBuilder};
+    ///
+    /// let fs = ClientBuilder::new("default")
+    ///     .with_user("default")
+    ///     .connect()
+    ///     .expect("client connect succeed");
+    /// let removed = fs.remove_dir_all_if_exists("/tmp/xxx/");
+    /// ```
+    pub fn remove_dir_all_if_exists(&self, path: &str) -> io::Result<bool> {
+        let res = self.remove_dir_all(path);
+        self.removed_or_absent(path, res)
+    }
+
+    /// Remove many paths and returns the result of each path in order.
+    ///
+    /// Failure on one path doesn't abort the rest. With `recursive`, every
+    /// path is removed like [`Client::remove_dir_all`]. Otherwise, like
+    /// [`Client::remove_file`] which removes files and empty dirs only.
+    ///
+    /// Paths are removed one by one on the current thread. `Client` is `Sync`,
+    /// callers could split `paths` across threads to remove them concurrently.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use hdrs::{Client, ClientBuilder};
+    ///
+    /// let fs = ClientBuilder::new("default")
+    ///     .with_user("default")
+    ///     .connect()
+    ///     .expect("client connect succeed");
+    /// for (path, res) in fs.remove_many(&["/tmp/job/_temporary", "/tmp/job/.staging"], true) {
+    ///     if let Err(e) = res {
+    ///         println!("remove {path} failed: {e}");
+    ///     }
+    /// }
+    /// ```
+    pub fn remove_many(&self, paths: &[&str], recursive: bool) -> Vec<(String, io::Result<()>)> {
+        paths
+            .iter()
+            .map(|path| {
+                let res = if recursive {
+                    self.remove_dir_all(path)
+                } else {
+                    self.remove_file(path)
+                };
+                (path.to_string(), res)
+            })
+            .collect()
+    }
+
+    /// Convert the result of removing `path` into whether it existed.
+    ///
+    /// `hdfsDelete` reports a missing path as a generic `EIO`, so we stat
+    /// the path to confirm it's absent on failure.
+    fn removed_or_absent(&self, path: &str, res: io::Result<()>) -> io::Result<bool> {
+        match res {
+            Ok(()) => Ok(true),
+            Err(e) => match self.metadata(path) {
+                Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(false),
+                _ => Err(e),
+            },
+        }
+    }
+
+    /// Stat a path to get file info.
+    ///
+    /// # ViewFS
+    ///
+    /// libhdfs stats via java's `FileSystem.getFileStatus`, which always
+    /// resolves through ViewFS mount links: stating a mount link returns the
+    /// metadata of its target in the backing filesystem, reported under the
+    /// `viewfs://` path. Internal dirs of the mount table (parents of mount
+    /// links) are reported as read-only dirs. There is no way to stat a mount
+    /// link itself via libhdfs.
+    ///
+    /// # Examples
+    ///
+    /// ## Stat a path to file info
+    ///
+    /// ```no_run
+    /// use hdrs::{Client, ClientBuilder};
+    ///
+    /// let fs = ClientBuilder::new("default")
+    ///     .with_user("default")
+    ///     .connect()
+    ///     .expect("client connect succeed");
+    /// let fi = fs.metadata("/tmp/hello.txt");
+    /// ```
+    ///
+    /// ## Stat a non-exist path
+    ///
+    /// ```no_run
+    /// use std::io;
+    ///
+    /// use hdrs::{Client, ClientBuilder};
+    ///
+    /// let fs = ClientBuilder::new("default")
+    ///     .with_user("default")
+    ///     .connect()
+    ///     .expect("client connect succeed");
+    /// let fi = fs.metadata("/tmp/not-exist.txt");
+    /// assert!(fi.is_err());
+    /// assert_eq!(fi.unwrap_err().kind(), io::ErrorKind::NotFound)
+    /// ```
+    pub fn metadata(&self, path: &str) -> io::Result<Metadata> {
+        if let Some(timeout) = self.operation_timeout {
+            let path = path.to_string();
+            return self.run_with_timeout(timeout, move |fs| fs.metadata(&path));
+        }
+
+        set_errno(Errno(0));
+
+        let hfi = unsafe {
+            let p = CString::new(path)?;
+            hdfsGetPathInfo(self.fs, p.as_ptr())
+        };
+
+        if hfi.is_null() {
+            return Err(io::Error::last_os_error());
+        }
+
+        // Make sure hfi will be freed even if the conversion panics.
+        let hfi = FileInfoGuard {
+            ptr: hfi,
+            entries: 1,
+        };
+
+        // Safety: hfi must be valid
+        let fi = unsafe { Metadata::from(*hfi.ptr) };
+
+        Ok(fi)
+    }
+
+    /// readdir will read file entries from a dir.
+    ///
+    /// # Errors
+    ///
+    /// - [`NotFound`][io::ErrorKind::NotFound]: the path doesn't exist.
+    /// - [`NotADirectory`][io::ErrorKind::NotADirectory]: the path is a file.
+    ///
+    /// Use [`Client::list_status`] if Hadoop's `listStatus` behavior is wanted.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use hdrs::{Client, ClientBuilder};
+    ///
+    /// let fs = ClientBuilder::new("default")
+    ///     .with_user("default")
+    ///     .connect()
+    ///     .expect("client connect succeed");
+    /// let fis = fs.read_dir("/tmp/hello/");
+    /// ```
+    pub fn read_dir(&self, path: &str) -> io::Result<Readdir> {
+        if let Some(timeout) = self.operation_timeout {
+            let path = path.to_string();
+            return self.run_with_timeout(timeout, move |fs| fs.read_dir(&path));
+        }
+
+        let fis = self.list_directory(path, |hfi| Metadata::from(*hfi))?;
+
+        Ok(fis.into())
+    }
+
+    /// read_dir_with will read file entries that match `filter` from a dir.
+    ///
+    /// Entries are filtered by the name and kind returned by hdfs before being
+    /// converted, so non-matching entries never allocate their [`Metadata`].
+    /// The filter is applied on the client side, the full listing is still
+    /// fetched from namenode.
+    /// Invalid utf-8 sequences in names are replaced with `U+FFFD` before
+    /// matching.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use hdrs::{Client, ClientBuilder, EntryFilter, FileKind};
+    ///
+    /// let fs = ClientBuilder::new("default")
+    ///     .with_user("default")
+    ///     .connect()
+    ///     .expect("client connect succeed");
+    /// let filter = EntryFilter::new()
+    ///     .with_suffix(".parquet")
+    ///     .with_kind(FileKind::File);
+    /// let readdir = fs.read_dir_with("/tmp/hello/", filter);
+    /// ```
+    pub fn read_dir_with(&self, path: &str, filter: EntryFilter) -> io::Result<Readdir> {
+        let fis = self.list_directory(path, |hfi| {
+            let name = unsafe { CStr::from_ptr(hfi.mName) }.to_string_lossy();
+            filter
+                .matches(metadata::file_name(&name), FileKind::from(hfi.mKind))
+                .then(|| Metadata::from(*hfi))
+        })?;
+
+        Ok(fis.into_iter().flatten().collect::<Vec<_>>().into())
+    }
+
+    /// list_status will list a path like Hadoop's `FileSystem.listStatus`.
+    ///
+    /// The only difference from [`Client::read_dir`] is that listing a file
+    /// returns a single entry with the file's own metadata instead of an
+    /// error, which is what tools ported from Hadoop's Java API expect.
+    ///
+    /// # Errors
+    ///
+    /// - [`NotFound`][io::ErrorKind::NotFound]: the path doesn't exist.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use hdrs::{Client, ClientBuilder};
+    ///
+    /// let fs = ClientBuilder::new("default")
+    ///     .with_user("default")
+    ///     .connect()
+    ///     .expect("client connect succeed");
+    /// let fis = fs.list_status("/tmp/hello.txt");
+    /// ```
+    pub fn list_status(&self, path: &str) -> io::Result<Readdir> {
+        match self.read_dir(path) {
+            Err(e) if e.raw_os_error() == Some(libc::ENOTDIR) => {
+                Ok(vec![self.metadata(path)?].into())
+            }
+            v => v,
+        }
+    }
+
+    /// read_dir_map will read file entries from a dir keyed by their
+    /// [`file_name`][Metadata::file_name].
+    ///
+    /// Names in a dir are unique on hdfs. If duplicate names appear anyway,
+    /// the last entry returned by hdfs wins.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use hdrs::{Client, ClientBuilder};
+    ///
+    /// let fs = ClientBuilder::new("default")
+    ///     .with_user("default")
+    ///     .connect()
+    ///     .expect("client connect succeed");
+    /// let entries = fs.read_dir_map("/tmp/hello/").expect("read dir succeed");
+    /// let meta = entries.get("world.txt");
+    /// ```
+    pub fn read_dir_map(&self, path: &str) -> io::Result<HashMap<String, Metadata>> {
+        let fis = self.read_dir(path)?;
+
+        Ok(fis
+            .into_inner()
+            .map(|m| (m.file_name().to_string(), m))
+            .collect())
+    }
+
+    /// find_in_dir returns the first entry in a dir that matches `pred`.
+    ///
+    /// # Notes
+    ///
+    /// `hdfsListDirectory` fetches the whole listing before returning, so
+    /// only the search stops early while the listing is still fully fetched.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use hdrs::{Client, ClientBuilder};
+    ///
+    /// let fs = ClientBuilder::new("default")
+    ///     .with_user("default")
+    ///     .connect()
+    ///     .expect("client connect succeed");
+    /// let first_dir = fs.find_in_dir("/tmp/hello/", |m| m.is_dir());
+    /// ```
+    pub fn find_in_dir(
+        &self,
+        path: &str,
+        pred: impl Fn(&Metadata) -> bool,
+    ) -> io::Result<Option<Metadata>> {
+        let fis = self.read_dir(path)?;
+
+        Ok(fis.into_inner().find(|m| pred(m)))
+    }
+
+    /// read_dir_sorted_by will read file entries from a dir sorted by `key`.
+    ///
+    /// All sort modes buffer the full listing in memory before sorting.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use hdrs::{Client, ClientBuilder, SortKey};
+    ///
+    /// let fs = ClientBuilder::new("default")
+    ///     .with_user("default")
+    ///     .connect()
+    ///     .expect("client connect succeed");
+    /// let newest_first = fs.read_dir_sorted_by("/tmp/hello/", SortKey::ModifiedDesc);
+    /// ```
+    pub fn read_dir_sorted_by(&self, path: &str, key: SortKey) -> io::Result<Vec<Metadata>> {
+        let mut fis = self.list_directory(path, |hfi| Metadata::from(*hfi))?;
+        key.sort(&mut fis);
+
+        Ok(fis)
+    }
+
+    /// diff_dir compares the current listing of a dir with a `previous` one.
+    ///
+    /// This is not recursive, only immediate children are compared.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use hdrs::{Client, ClientBuilder};
+    ///
+    /// let fs = ClientBuilder::new("default")
+    ///     .with_user("default")
+    ///     .connect()
+    ///     .expect("client connect succeed");
+    /// let previous: Vec<_> = fs
+    ///     .read_dir("/tmp/hello/")
+    ///     .expect("read dir must succeed")
+    ///     .into_inner()
+    ///     .collect();
+    /// let diff = fs.diff_dir("/tmp/hello/", &previous);
+    /// ```
+    pub fn diff_dir(&self, path: &str, previous: &[Metadata]) -> io::Result<DirDiff> {
+        let current = self.list_directory(path, |hfi| Metadata::from(*hfi))?;
+
+        Ok(DirDiff::new(current, previous))
+    }
+
+    /// dir_size returns the total size of the immediate children of a dir.
+    ///
+    /// This is **not recursive**: sub-directories are counted as zero bytes and
+    /// their contents are not included. It's different from content summary
+    /// like `hdfs dfs -du -s`.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use hdrs::{Client, ClientBuilder};
+    ///
+    /// let fs = ClientBuilder::new("default")
+    ///     .with_user("default")
+    ///     .connect()
+    ///     .expect("client connect succeed");
+    /// let size = fs.dir_size("/tmp/hello/");
+    /// ```
+    pub fn dir_size(&self, path: &str) -> io::Result<u64> {
+        let sizes = self.list_directory(path, |hfi| hfi.mSize as u64)?;
+
+        Ok(sizes.into_iter().sum())
+    }
+
+    /// read_dir_light will read lightweight file entries from a dir.
+    ///
+    /// Only path, size, kind and modification time are decoded, which is
+    /// cheaper than [`Client::read_dir`] for huge listings. Use
+    /// [`DirEntry::metadata`] to fetch the full metadata of an entry.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use hdrs::{Client, ClientBuilder};
+    ///
+    /// let fs = ClientBuilder::new("default")
+    ///     .with_user("default")
+    ///     .connect()
+    ///     .expect("client connect succeed");
+    /// let entries = fs.read_dir_light("/tmp/hello/");
+    /// ```
+    pub fn read_dir_light(&self, path: &str) -> io::Result<Vec<DirEntry>> {
+        // Entries keep the instance alive and stat with the same timeout.
+        let client = Arc::new(Client {
+            operation_timeout: self.operation_timeout,
+            ..self.share()
+        });
+        self.list_directory(path, |hfi| DirEntry::new(client.clone(), hfi))
+    }
+
+    /// list_names will read the names of entries in a dir, like `file` for
+    /// `/path/to/dir/file`.
+    ///
+    /// # Notes
+    ///
+    /// libhdfs doesn't expose a names-only listing RPC, so this still fetches
+    /// the full file status of every entry from namenode. Only the name is
+    /// decoded from them, which saves allocating owner, group and path for
+    /// every entry compared to [`Client::read_dir`].
+    ///
+    /// Invalid utf-8 sequences in names are replaced with `U+FFFD`.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use hdrs::{Client, ClientBuilder};
+    ///
+    /// let fs = ClientBuilder::new("default")
+    ///     .with_user("default")
+    ///     .connect()
+    ///     .expect("client connect succeed");
+    /// let names = fs.list_names("/tmp/hello/");
+    /// ```
+    pub fn list_names(&self, path: &str) -> io::Result<Vec<String>> {
+        self.list_directory(path, |hfi| {
+            // HDFS allows names that are not valid utf-8, replace the invalid
+            // bytes instead of failing the whole listing.
+            let name = unsafe { CStr::from_ptr(hfi.mName) }.to_string_lossy();
+            metadata::file_name(&name).to_string()
+        })
+    }
+
+    /// for_each_entry will call `f` on every entry of a dir.
+    ///
+    /// Entries are converted into [`Metadata`] one by one right before `f` is
+    /// called, so no `Vec` of entries is collected. Iteration stops at the
+    /// first error returned by `f` and that error is returned.
+    ///
+    /// # Notes
+    ///
+    /// libhdfs still fetches the whole listing from namenode before the first
+    /// call of `f`, and it's freed once iteration stops, even if `f` panics.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use hdrs::{Client, ClientBuilder};
+    ///
+    /// let fs = ClientBuilder::new("default")
+    ///     .with_user("default")
+    ///     .connect()
+    ///     .expect("client connect succeed");
+    /// let mut total = 0;
+    /// fs.for_each_entry("/tmp/hello/", |meta| {
+    ///     total += meta.len();
+    ///     Ok(())
+    /// })
+    /// .expect("for each entry succeed");
+    /// ```
+    pub fn for_each_entry(
+        &self,
+        path: &str,
+        mut f: impl FnMut(&Metadata) -> io::Result<()>,
+    ) -> io::Result<()> {
+        self.visit_directory(path, |hfi| f(&Metadata::from(*hfi)))
+    }
+
+    /// read_dir_channel will read file entries from a dir and send them into `tx`.
+    ///
+    /// The listing runs on the blocking thread pool, and entries are sent one by
+    /// one so the channel's capacity bounds the memory used by consumers. Error
+    /// will be sent as the last item. `tx` will be dropped to close the channel on
+    /// completion, error or when receiver has been dropped.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use futures::channel::mpsc;
+    /// use futures::StreamExt;
+    /// use hdrs::{Client, ClientBuilder};
+    ///
+    /// # async fn example() {
+    /// let fs = ClientBuilder::new("default")
+    ///     .with_user("default")
+    ///     .connect()
+    ///     .expect("client connect succeed");
+    /// let (tx, mut rx) = mpsc::channel(128);
+    /// let list = fs.read_dir_channel("/tmp/hello/", tx);
+    /// let consume = async {
+    ///     while let Some(entry) = rx.next().await {
+    ///         let _ = entry.expect("read dir must succeed");
+    ///     }
+    /// };
+    /// futures::join!(list, consume);
+    /// # }
+    /// ```
+    #[cfg(feature = "async_file")]
+    pub async fn read_dir_channel(&self, path: &str, mut tx: Sender<io::Result<Metadata>>) {
+        // The listing keeps running on the blocking thread pool even if this
+        // future is dropped, so it must not borrow the instance from `self`.
+        let client = self.share();
+        let path = path.to_string();
+
+        match blocking::unblock(move || client.read_dir(&path)).await {
+            Ok(rd) => {
+                for fi in rd.into_inner() {
+                    // Receiver has been dropped, stop sending.
+                    if tx.send(Ok(fi)).await.is_err() {
+                        return;
+                    }
+                }
+            }
+            Err(e) => {
+                let _ = tx.send(Err(e)).await;
+            }
+        }
+    }
+
+    /// read_dir_prefetch will read file entries from a dir, and prefetch the
+    /// entries of every sub dir concurrently.
+    ///
+    /// Every item of the returned stream is an entry of `path` with its
+    /// prefetched children, `None` for files. Items are yielded in listing
+    /// order, and at most `concurrency` sub dirs are listed at the same time
+    /// on the blocking thread pool to avoid overwhelming the name node. Errors
+    /// of listing a sub dir are returned along with it instead of stopping
+    /// the stream.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use std::num::NonZeroUsize;
+    ///
+    /// use futures::StreamExt;
+    /// use hdrs::{Client, ClientBuilder};
+    ///
+    /// # async fn example() -> std::io::Result<()> {
+    /// let fs = ClientBuilder::new("default")
+    ///     .with_user("default")
+    ///     .connect()
+    ///     .expect("client connect succeed");
+    /// let concurrency = NonZeroUsize::new(8).unwrap();
+    /// let mut entries = fs.read_dir_prefetch("/tmp/hello/", concurrency).await?;
+    /// while let Some((meta, children)) = entries.next().await {
+    ///     if let Some(children) = children {
+    ///         println!("{} has {} children", meta.path(), children?.len());
+    ///     }
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "async_file")]
+    pub async fn read_dir_prefetch(
+        &self,
+        path: &str,
+        concurrency: NonZeroUsize,
+    ) -> io::Result<impl Stream<Item = (Metadata, Option<io::Result<Readdir>>)> + Send + 'static>
+    {
+        // The returned stream is `'static`, so it keeps an owned instance
+        // alive instead of borrowing it from `self`.
+        let client = Arc::new(self.share());
+
+        let rd = {
+            let client = client.clone();
+            let path = path.to_string();
+            blocking::unblock(move || client.read_dir(&path)).await?
+        };
+
+        Ok(stream::iter(rd.into_inner())
+            .map(move |meta| {
+                let client = client.clone();
+                async move {
+                    if !meta.is_dir() {
+                        return (meta, None);
+                    }
+
+                    let path = meta.path().to_string();
+                    let children = blocking::unblock(move || client.read_dir(&path)).await;
+                    (meta, Some(children))
+                }
+            })
+            .buffered(concurrency.get()))
+    }
+
+    /// List entries of a dir and convert every `hdfsFileInfo` via `f`.
+    fn list_directory<T>(&self, path: &str, f: impl Fn(&hdfsFileInfo) -> T) -> io::Result<Vec<T>> {
+        let mut fis = Vec::new();
+        self.visit_directory(path, |hfi| {
+            fis.push(f(hfi));
+            Ok(())
+        })?;
+
+        Ok(fis)
+    }
+
+    /// List entries of a dir and call `f` on every `hdfsFileInfo`, stopping
+    /// at the first error returned by `f`.
+    fn visit_directory(
+        &self,
+        path: &str,
+        mut f: impl FnMut(&hdfsFileInfo) -> io::Result<()>,
+    ) -> io::Result<()> {
+        set_errno(Errno(0));
+
+        let mut entries = 0;
+        let hfis = unsafe {
+            let p = CString::new(path)?;
+            hdfsListDirectory(self.fs, p.as_ptr(), &mut entries)
+        };
+
+        // hfis will be NULL on error or empty directory.
+        //
+        // errno is not reliable to tell them apart since libhdfs could leave
+        // it stale from an unrelated call inside JNI, so we confirm by stat.
+        // - If stat failed, return the stat error like `NotFound`.
+        // - If the path is a file, return `NotADirectory`.
+        // - If the path is a dir, it's empty unless errno reports permission
+        //   errors, which stat can't detect since it doesn't need to read the dir.
+        if hfis.is_null() {
+            let e = io::Error::last_os_error();
+
+            let meta = self.metadata(path)?;
+            if !meta.is_dir() {
+                return Err(io::Error::from_raw_os_error(libc::ENOTDIR));
+            }
+            return match e.raw_os_error() {
+                Some(libc::EACCES) | Some(libc::EPERM) => Err(e),
+                _ => Ok(()),
+            };
+        }
+
+        // Make sure hfis will be freed even if `f` fails or panics.
+        let hfis = FileInfoGuard { ptr: hfis, entries };
+
+        // hdfs returns the file itself while listing a file, we need to stat
+        // the path to tell it apart from a dir that contains only one file.
+        if entries == 1 && unsafe { (*hfis.ptr).mKind } == tObjectKind_kObjectKindFile {
+            let is_file = self.metadata(path).map(|m| m.is_file());
+            if let Ok(true) = is_file {
+                return Err(io::Error::from_raw_os_error(libc::ENOTDIR));
+            }
+        }
+
+        for i in 0..entries {
+            unsafe { f(&*hfis.ptr.offset(i as isize))? };
+        }
+
+        Ok(())
+    }
+
+    /// mkdir create dir and all it's parent directories.
+    ///
+    /// The behavior is similar to `mkdir -p /path/to/dir`.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use hdrs::{Client, ClientBuilder};
+    ///
+    /// let fs = ClientBuilder::new("default")
+    ///     .with_user("default")
+    ///     .connect()
+    ///     .expect("client connect succeed");
+    /// let _ = fs.create_dir("/tmp");
+    /// ```
+    pub fn create_dir(&self, path: &str) -> io::Result<()> {
+        if let Some(timeout) = self.operation_timeout {
+            let path = path.to_string();
+            return self.run_with_timeout(timeout, move |fs| fs.create_dir(&path));
+        }
+
+        let n = unsafe {
+            let p = CString::new(path)?;
+            hdfsCreateDirectory(self.fs, p.as_ptr())
+        };
+
+        if n == -1 {
+            return Err(io::Error::last_os_error());
+        }
+
+        Ok(())
+    }
+
+    /// Set the permission of a file or dir, like `chmod`.
+    ///
+    /// Returns [`InvalidInput`][io::ErrorKind::InvalidInput] if `mode` is out
+    /// of `0..=0o7777` instead of truncating it.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use hdrs::{Client, ClientBuilder};
+    ///
+    /// let fs = ClientBuilder::new("default")
+    ///     .with_user("default")
+    ///     .connect()
+    ///     .expect("client connect succeed");
+    /// let _ = fs.set_permission("/tmp/hello.txt", 0o644);
+    /// ```
+    pub fn set_permission(&self, path: &str, mode: i16) -> io::Result<()> {
+        check_mode(mode)?;
+        debug!("set permission of {} to {:o}", path, mode);
+
+        let n = unsafe {
+            let p = CString::new(path)?;
+            hdfsChmod(self.fs, p.as_ptr(), mode)
+        };
+
+        if n == -1 {
+            return Err(io::Error::last_os_error());
+        }
+
+        Ok(())
+    }
+
+    /// Set the permission of `path` and everything under it, like
+    /// `hdfs dfs -chmod -R`.
+    ///
+    /// Use [`Client::set_permission_recursive_split`] to apply different
+    /// modes to files and dirs.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use hdrs::{Client, ClientBuilder};
+    ///
+    /// let fs = ClientBuilder::new("default")
+    ///     .with_user("default")
+    ///     .connect()
+    ///     .expect("client connect succeed");
+    /// let _ = fs.set_permission_recursive("/tmp/xxx", 0o750);
+    /// ```
+    pub fn set_permission_recursive(&self, path: &str, mode: i16) -> io::Result<()> {
+        self.set_permission_recursive_split(path, mode, mode)
+    }
+
+    /// Set the permission of `path` and everything under it with `dir_mode`
+    /// for dirs and `file_mode` for files, like `0o755` and `0o644`.
+    ///
+    /// Dirs are updated before being listed, the same as `hdfs dfs -chmod -R`,
+    /// so `dir_mode` must keep read and execute bits for the current user.
+    /// Stops at the first error, which carries the path that failed. Entries
+    /// updated before that are not rolled back.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use hdrs::{Client, ClientBuilder};
+    ///
+    /// let fs = ClientBuilder::new("default")
+    ///     .with_user("default")
+    ///     .connect()
+    ///     .expect("client connect succeed");
+    /// let _ = fs.set_permission_recursive_split("/tmp/xxx", 0o755, 0o644);
+    /// ```
+    pub fn set_permission_recursive_split(
+        &self,
+        path: &str,
+        dir_mode: i16,
+        file_mode: i16,
+    ) -> io::Result<()> {
+        let with_context = |path: &str, e: io::Error| {
+            io::Error::new(e.kind(), format!("set permission of {path}: {e}"))
+        };
+
+        let mut stack = vec![self.metadata(path).map_err(|e| with_context(path, e))?];
+        while let Some(meta) = stack.pop() {
+            let mode = if meta.is_dir() { dir_mode } else { file_mode };
+            self.set_permission(meta.path(), mode)
+                .map_err(|e| with_context(meta.path(), e))?;
+
+            if meta.is_dir() {
+                let entries = self
+                    .read_dir(meta.path())
+                    .map_err(|e| with_context(meta.path(), e))?;
+                stack.extend(entries.into_inner());
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Set the owner and group of a file or dir, like `chown`.
+    ///
+    /// `None` keeps the current value. Changing owner requires superuser.
+    fn set_owner(&self, path: &str, owner: Option<&str>, group: Option<&str>) -> io::Result<()> {
+        debug!("set owner of {} to {:?}:{:?}", path, owner, group);
+
+        let p = CString::new(path)?;
+        let owner = owner.map(CString::new).transpose()?;
+        let group = group.map(CString::new).transpose()?;
+        let n = unsafe {
+            hdfsChown(
+                self.fs,
+                p.as_ptr(),
+                owner.as_ref().map_or(ptr::null(), |v| v.as_ptr()),
+                group.as_ref().map_or(ptr::null(), |v| v.as_ptr()),
+            )
+        };
+
+        if n == -1 {
+            return Err(io::Error::last_os_error());
+        }
+
+        Ok(())
+    }
+
+    /// Set the replication of a file.
+    fn set_replication(&self, path: &str, replication: i16) -> io::Result<()> {
+        debug!("set replication of {} to {}", path, replication);
+
+        let n = unsafe {
+            let p = CString::new(path)?;
+            hdfsSetReplication(self.fs, p.as_ptr(), replication)
+        };
+
+        if n == -1 {
+            return Err(io::Error::last_os_error());
+        }
+
+        Ok(())
+    }
+
+    /// Set the modification and access time of a file or dir, like `touch -d`.
+    ///
+    /// `None` keeps the current value. HDFS stores times in milliseconds, but
+    /// libhdfs only accepts seconds, so sub-second parts are truncated.
+    fn set_times(
+        &self,
+        path: &str,
+        modified: Option<SystemTime>,
+        accessed: Option<SystemTime>,
+    ) -> io::Result<()> {
+        debug!("set times of {} to {:?} {:?}", path, modified, accessed);
+
+        // libhdfs takes `-1` as no change.
+        let secs = |t: Option<SystemTime>| -> io::Result<tTime> {
+            let t = match t {
+                Some(t) => t,
+                None => return Ok(-1),
+            };
+            let d = t.duration_since(UNIX_EPOCH).map_err(|_| {
+                io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    format!("time {t:?} must not be earlier than unix epoch"),
+                )
+            })?;
+            tTime::try_from(d.as_secs()).map_err(|_| {
+                io::Error::new(io::ErrorKind::InvalidInput, format!("time {t:?} overflows"))
+            })
+        };
+        let (mtime, atime) = (secs(modified)?, secs(accessed)?);
+
+        let n = unsafe {
+            let p = CString::new(path)?;
+            hdfsUtime(self.fs, p.as_ptr(), mtime, atime)
+        };
+
+        if n == -1 {
+            return Err(io::Error::last_os_error());
+        }
+
+        Ok(())
+    }
+
+    /// Copy `src` to `dst` within the same cluster, dirs will be copied
+    /// recursively.
+    ///
+    /// The data is streamed through this client. Attributes like permission
+    /// and owner are not preserved.
+    fn copy(&self, src: &str, dst: &str) -> io::Result<()> {
+        debug!("copy file from {} to {}", src, dst);
+
+        let n = unsafe {
+            let src = CString::new(src)?;
+            let dst = CString::new(dst)?;
+            hdfsCopy(self.fs, src.as_ptr(), self.fs, dst.as_ptr())
+        };
+
+        if n == -1 {
+            return Err(io::Error::last_os_error());
+        }
+
+        Ok(())
+    }
+
+    /// Copy `src` to `dst` and preserve the attributes of `src`, like `cp -p`.
+    ///
+    /// Permission, owner and group, replication (files only) and times are
+    /// applied to `dst` after data copied. Failing to preserve an attribute
+    /// doesn't fail the copy: they are returned as `(attribute, error)` pairs
+    /// instead, where attribute is one of `permission`, `owner`,
+    /// `replication` and `times`. For example, changing owner requires
+    /// superuser.
+    ///
+    /// For dirs, only attributes of `dst` itself are preserved, not its
+    /// children.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use hdrs::{Client, ClientBuilder};
+    ///
+    /// let fs = ClientBuilder::new("default")
+    ///     .with_user("default")
+    ///     .connect()
+    ///     .expect("client connect succeed");
+    /// let failed = fs
+    ///     .copy_preserve("/tmp/hello.txt", "/tmp/hello.txt.bak")
+    ///     .expect("copy must succeed");
+    /// for (attr, err) in failed {
+    ///     println!("{attr} is not preserved: {err}");
+    /// }
+    /// ```
+    pub fn copy_preserve(
+        &self,
+        src: &str,
+        dst: &str,
+    ) -> io::Result<Vec<(&'static str, io::Error)>> {
+        let meta = self.metadata(src)?;
+        self.copy(src, dst)?;
+
+        // Use parts to avoid panics on invalid times.
+        let parts = meta.to_parts();
+        let time = |ms: i64| Some(UNIX_EPOCH + Duration::from_millis(ms.max(0) as u64));
+
+        let mut failed = Vec::new();
+        if let Err(e) = self.set_permission(dst, meta.permissions()) {
+            failed.push(("permission", e));
+        }
+        if let Err(e) = self.set_owner(dst, meta.owner_opt(), meta.group_opt()) {
+            failed.push(("owner", e));
+        }
+        if !meta.is_dir() {
+            if let Err(e) = self.set_replication(dst, meta.replication()) {
+                failed.push(("replication", e));
+            }
+        }
+        if let Err(e) = self.set_times(dst, time(parts.modified_ms), time(parts.accessed_ms)) {
+            failed.push(("times", e));
+        }
+
+        for (attr, e) in &failed {
+            debug!("copy {} to {}: {} is not preserved: {}", src, dst, attr, e);
+        }
+        Ok(failed)
+    }
+
+    /// Create a zero-length file with specified block size and replication.
+    ///
+    /// HDFS doesn't support preallocation or extending a file with holes, so
+    /// this only creates an empty file whose block size and replication are
+    /// fixed at creation time. Pass `0` to use the default configured values.
+    /// The file will be overwritten if it already exists.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use hdrs::{Client, ClientBuilder};
+    ///
+    /// let fs = ClientBuilder::new("default")
+    ///     .with_user("default")
+    ///     .connect()
+    ///     .expect("client connect succeed");
+    /// let _ = fs.create_empty("/tmp/placeholder", 256 * 1024 * 1024, 3);
+    /// ```
+    pub fn create_empty(&self, path: &str, block_size: i64, replication: i16) -> io::Result<()> {
+        let block_size = usize::try_from(block_size).map_err(|_| {
+            io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("`block_size` {block_size} must not be negative"),
+            )
+        })?;
+        let replication = usize::try_from(replication).map_err(|_| {
+            io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("`replication` {replication} must not be negative"),
+            )
+        })?;
+
+        self.open_file()
+            .write(true)
+            .create(true)
+            .with_blocksize(block_size)
+            .with_replication(replication)
+            .open(path)?
+            .flush_and_close()
+    }
+
+    /// Set the erasure coding policy of a dir, like `RS-6-3-1024k`.
+    ///
+    /// # Notes
+    ///
+    /// Always returns [`Unsupported`][io::ErrorKind::Unsupported], see
+    /// [unsupported operations](Client#unsupported-operations).
+    ///
+    /// Files written under an erasure coded dir will follow its policy, and
+    /// their [`Metadata::replication`] will be reported as `1`.
+    pub fn set_erasure_coding_policy(&self, path: &str, policy: &str) -> io::Result<()> {
+        debug!("set erasure coding policy {} on {}", policy, path);
+        Err(unsupported("erasure coding policy"))
+    }
 
-        if n == -1 {
+    /// Get the erasure coding policy of a path, `None` if the path is replicated.
+    ///
+    /// # Notes
+    ///
+    /// Always returns [`Unsupported`][io::ErrorKind::Unsupported], see
+    /// [unsupported operations](Client#unsupported-operations).
+    pub fn get_erasure_coding_policy(&self, path: &str) -> io::Result<Option<String>> {
+        debug!("get erasure coding policy on {}", path);
+        Err(unsupported("erasure coding policy"))
+    }
+
+    /// Unset the erasure coding policy of a dir.
+    ///
+    /// # Notes
+    ///
+    /// Always returns [`Unsupported`][io::ErrorKind::Unsupported], see
+    /// [unsupported operations](Client#unsupported-operations).
+    pub fn unset_erasure_coding_policy(&self, path: &str) -> io::Result<()> {
+        debug!("unset erasure coding policy on {}", path);
+        Err(unsupported("erasure coding policy"))
+    }
+
+    /// Create a snapshot of a snapshottable dir, returns the snapshot path
+    /// like `/path/.snapshot/name`.
+    ///
+    /// A default name based on the current time will be used by hdfs if
+    /// `name` is `None`.
+    ///
+    /// # Notes
+    ///
+    /// Always returns [`Unsupported`][io::ErrorKind::Unsupported], see
+    /// [unsupported operations](Client#unsupported-operations).
+    ///
+    /// An admin must make the dir snapshottable by
+    /// `hdfs dfsadmin -allowSnapshot <path>` first.
+    pub fn create_snapshot(&self, path: &str, name: Option<&str>) -> io::Result<String> {
+        debug!("create snapshot {:?} on {}", name, path);
+        Err(unsupported("snapshot"))
+    }
+
+    /// Delete a snapshot of a snapshottable dir.
+    ///
+    /// # Notes
+    ///
+    /// Always returns [`Unsupported`][io::ErrorKind::Unsupported], see
+    /// [unsupported operations](Client#unsupported-operations).
+    pub fn delete_snapshot(&self, path: &str, name: &str) -> io::Result<()> {
+        debug!("delete snapshot {} on {}", name, path);
+        Err(unsupported("snapshot"))
+    }
+
+    /// Rename a snapshot of a snapshottable dir.
+    ///
+    /// # Notes
+    ///
+    /// Always returns [`Unsupported`][io::ErrorKind::Unsupported], see
+    /// [unsupported operations](Client#unsupported-operations).
+    pub fn rename_snapshot(&self, path: &str, old_name: &str, new_name: &str) -> io::Result<()> {
+        debug!("rename snapshot {} -> {} on {}", old_name, new_name, path);
+        Err(unsupported("snapshot"))
+    }
+
+    /// Get the space usage of the filesystem backing `path`, like `df`.
+    ///
+    /// # Notes
+    ///
+    /// libhdfs only exposes cluster-wide `hdfsGetCapacity` and `hdfsGetUsed`
+    /// of the connected name node, so the stats are the same for every path
+    /// on this client. In federated setups, connect a client to the name node
+    /// serving the path to get its own stats.
+    ///
+    /// `path` is stated first so that errors like
+    /// [`NotFound`][io::ErrorKind::NotFound] are still reported.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use hdrs::{Client, ClientBuilder};
+    ///
+    /// let fs = ClientBuilder::new("default")
+    ///     .with_user("default")
+    ///     .connect()
+    ///     .expect("client connect succeed");
+    /// let stats = fs.statfs("/tmp").expect("statfs succeed");
+    /// println!("{} of {} bytes used", stats.used, stats.capacity);
+    /// ```
+    pub fn statfs(&self, path: &str) -> io::Result<FsStats> {
+        self.metadata(path)?;
+
+        set_errno(Errno(0));
+        let capacity = unsafe { hdfsGetCapacity(self.fs) };
+        if capacity == -1 {
+            return Err(io::Error::last_os_error());
+        }
+        let used = unsafe { hdfsGetUsed(self.fs) };
+        if used == -1 {
             return Err(io::Error::last_os_error());
         }
 
-        debug!("delete dir all {} finished", path);
-        Ok(())
+        let (capacity, used) = (capacity as u64, used as u64);
+        Ok(FsStats {
+            capacity,
+            used,
+            remaining: capacity.saturating_sub(used),
+        })
     }
 
-    /// Stat a path to get file info.
+    /// Resolve `path` by following the symlink chain at most `max_hops` times,
+    /// returns the final target.
     ///
-    /// # Examples
+    /// # Notes
     ///
-    /// ## Stat a path to file info
+    /// Always returns [`Unsupported`][io::ErrorKind::Unsupported], see
+    /// [unsupported operations](Client#unsupported-operations).
+    ///
+    /// Other operations like [`Client::metadata`] and [`Client::open_file`]
+    /// already follow links transparently on the java side.
+    pub fn resolve_path(&self, path: &str, max_hops: usize) -> io::Result<String> {
+        debug!("resolve path {} with max hops {}", path, max_hops);
+        Err(unsupported("reading symlink targets"))
+    }
+
+    /// Start recovering the lease of a file, returns `true` if the file is
+    /// already closed.
+    ///
+    /// # Lease recovery
+    ///
+    /// Namenode grants a lease to the writer of a file. If the writer crashed,
+    /// other writers can't open the file until the soft limit (1 minute)
+    /// expires, and the namenode recovers the lease by itself after the hard
+    /// limit (1 hour by default). `recoverLease` triggers the recovery at
+    /// once, but it's asynchronous, so callers usually poll
+    /// [`Client::is_file_closed`] until `true` before reopening for append.
+    ///
+    /// # Notes
+    ///
+    /// Always returns [`Unsupported`][io::ErrorKind::Unsupported], see
+    /// [unsupported operations](Client#unsupported-operations).
+    pub fn recover_lease(&self, path: &str) -> io::Result<bool> {
+        debug!("recover lease of {}", path);
+        Err(unsupported("lease recovery"))
+    }
+
+    /// Check whether a file has been closed and its lease released.
+    ///
+    /// # Notes
+    ///
+    /// Always returns [`Unsupported`][io::ErrorKind::Unsupported], see
+    /// [unsupported operations](Client#unsupported-operations).
+    pub fn is_file_closed(&self, path: &str) -> io::Result<bool> {
+        debug!("check whether {} is closed", path);
+        Err(unsupported("lease recovery"))
+    }
+
+    /// Check whether the current user has `mode` access to `path`, like
+    /// POSIX `access(2)`.
+    ///
+    /// Returns [`PermissionDenied`][io::ErrorKind::PermissionDenied] if
+    /// access is not granted.
+    ///
+    /// # Notes
+    ///
+    /// Always returns [`Unsupported`][io::ErrorKind::Unsupported], see
+    /// [unsupported operations](Client#unsupported-operations).
+    ///
+    /// Checking permission bits from [`Metadata`] locally is not reliable
+    /// since group membership is resolved by namenode.
+    pub fn access(&self, path: &str, mode: AccessMode) -> io::Result<()> {
+        debug!("check access {:o} of {}", mode.bits(), path);
+        Err(unsupported("access check"))
+    }
+
+    /// List datanodes of the cluster with their usage and state.
+    ///
+    /// # Notes
+    ///
+    /// Always returns [`Unsupported`][io::ErrorKind::Unsupported], see
+    /// [unsupported operations](Client#unsupported-operations).
+    ///
+    /// Without superuser, the `Hadoop:service=NameNode,name=NameNodeInfo`
+    /// bean of namenode's JMX endpoint also lists datanodes. Use
+    /// [`Client::statfs`] for the usage of the whole cluster.
+    pub fn datanode_report(&self) -> io::Result<Vec<DatanodeInfo>> {
+        debug!("list datanodes");
+        Err(unsupported("datanode report"))
+    }
+
+    /// List names of storage policies available on the cluster, like `HOT`,
+    /// `COLD` and `ALL_SSD`.
+    ///
+    /// # Notes
+    ///
+    /// Always returns [`Unsupported`][io::ErrorKind::Unsupported], see
+    /// [unsupported operations](Client#unsupported-operations).
+    pub fn storage_policies(&self) -> io::Result<Vec<String>> {
+        debug!("list storage policies");
+        Err(unsupported("storage policy"))
+    }
+
+    /// Create dir and all it's parent directories, then stat it.
+    ///
+    /// The metadata of the existing dir will be returned if it already exists,
+    /// which is useful to check the effective permissions after umask.
+    ///
+    /// # Examples
     ///
     /// ```no_run
     /// use hdrs::{Client, ClientBuilder};
@@ -335,46 +2231,73 @@ impl Client {
     ///     .with_user("default")
     ///     .connect()
     ///     .expect("client connect succeed");
-    /// let fi = fs.metadata("/tmp/hello.txt");
+    /// let meta = fs.create_dir_and_stat("/tmp/xxx");
     /// ```
+    pub fn create_dir_and_stat(&self, path: &str) -> io::Result<Metadata> {
+        self.create_dir(path)?;
+        self.metadata(path)
+    }
+
+    /// Verify that `dst` has the same content as `src`.
     ///
-    /// ## Stat a non-exist path
+    /// Returns `false` if their sizes or contents mismatch.
     ///
-    /// ```no_run
-    /// use std::io;
+    /// # Notes
+    ///
+    /// libhdfs doesn't expose `getFileChecksum`, so we can't compare the
+    /// distributed checksums. Instead, both files will be streamed and compared
+    /// chunk by chunk. This costs a full read of both files, but a mismatch is
+    /// always a real difference: it's not affected by the block size or
+    /// bytes-per-checksum of files like the distributed checksum does.
+    ///
+    /// # Examples
     ///
+    /// ```no_run
     /// use hdrs::{Client, ClientBuilder};
     ///
     /// let fs = ClientBuilder::new("default")
     ///     .with_user("default")
     ///     .connect()
     ///     .expect("client connect succeed");
-    /// let fi = fs.metadata("/tmp/not-exist.txt");
-    /// assert!(fi.is_err());
-    /// assert_eq!(fi.unwrap_err().kind(), io::ErrorKind::NotFound)
+    /// let same = fs.verify_copy("/tmp/hello.txt", "/tmp/hello.txt.bak");
     /// ```
-    pub fn metadata(&self, path: &str) -> io::Result<Metadata> {
-        set_errno(Errno(0));
-
-        let hfi = unsafe {
-            let p = CString::new(path)?;
-            hdfsGetPathInfo(self.fs, p.as_ptr())
-        };
-
-        if hfi.is_null() {
-            return Err(io::Error::last_os_error());
+    pub fn verify_copy(&self, src: &str, dst: &str) -> io::Result<bool> {
+        if self.metadata(src)?.len() != self.metadata(dst)?.len() {
+            return Ok(false);
         }
 
-        // Safety: hfi must be valid
-        let fi = unsafe { Metadata::from(*hfi) };
-
-        // Make sure hfi has been freed.
-        unsafe { hdfsFreeFileInfo(hfi, 1) };
+        let src = self.open_file().read(true).open(src)?;
+        let dst = self.open_file().read(true).open(dst)?;
 
-        Ok(fi)
+        let (mut src_buf, mut dst_buf) = (vec![0; VERIFY_CHUNK_SIZE], vec![0; VERIFY_CHUNK_SIZE]);
+        let mut offset = 0;
+        loop {
+            let n = src.read_full_at(&mut src_buf, offset)?;
+            let m = dst.read_full_at(&mut dst_buf, offset)?;
+            if src_buf[..n] != dst_buf[..m] {
+                return Ok(false);
+            }
+            if n == 0 {
+                return Ok(true);
+            }
+            offset += n as u64;
+        }
     }
 
-    /// readdir will read file entries from a file.
+    /// Write `contents` to `path` by writing a sibling temp file first and
+    /// renaming it over `path`, parent dirs will be created if not exist.
+    ///
+    /// The temp file is named like `path._tmp_<suffix>`, and will be removed
+    /// if any step failed, unless the existing `path` has been moved away and
+    /// can't be restored. In that case, the temp file holding the new content
+    /// is kept and its path is included in the returned error.
+    ///
+    /// # Atomicity
+    ///
+    /// Readers will never see a partially written `path`. However, the
+    /// existing `path` is replaced by [`Client::rename_file_overwrite`], which
+    /// moves it aside before renaming, so readers could see the old content,
+    /// the new content, or `NotFound` for a short while in between.
     ///
     /// # Examples
     ///
@@ -385,49 +2308,119 @@ impl Client {
     ///     .with_user("default")
     ///     .connect()
     ///     .expect("client connect succeed");
-    /// let fis = fs.read_dir("/tmp/hello/");
+    /// fs.write_atomic("/tmp/_SUCCESS", b"").expect("write succeed");
     /// ```
-    pub fn read_dir(&self, path: &str) -> io::Result<Readdir> {
-        set_errno(Errno(0));
+    pub fn write_atomic(&self, path: &str, contents: &[u8]) -> io::Result<()> {
+        self.create_dir_for_file(path)?;
 
-        let mut entries = 0;
-        let hfis = unsafe {
-            let p = CString::new(path)?;
-            hdfsListDirectory(self.fs, p.as_ptr(), &mut entries)
-        };
+        let tmp_path = format!("{path}._tmp_{}", tmp_suffix());
+        debug!("write {} via temp file {}", path, tmp_path);
 
-        // hfis will be NULL on error or empty directory.
-        // We will try to check last_os_error's code.
-        // - If there is no error, return empty vec directly.
-        // - If errno == 0, there is no error, return empty vec directly.
-        // - If errno != 0, return the last os error.
-        if hfis.is_null() {
-            let e = io::Error::last_os_error();
+        let cleanup = |e: io::Error| {
+            if let Err(err) = self.remove_file(&tmp_path) {
+                debug!("remove temp file {} failed: {}", tmp_path, err);
+            }
+            e
+        };
 
-            return match e.raw_os_error() {
-                None => Ok(Vec::new().into()),
-                Some(0) => Ok(Vec::new().into()),
-                Some(_) => Err(e),
-            };
+        self.open_file()
+            .write(true)
+            .create(true)
+            .truncate(true)
+            .open(&tmp_path)
+            .and_then(|mut f| {
+                f.write_all(contents)?;
+                f.flush_and_close()
+            })
+            .map_err(cleanup)?;
+
+        let existed = self.metadata(path).is_ok();
+        if let Err(e) = self.rename_file_overwrite(&tmp_path, path) {
+            // The old content has been moved away and not restored, keep the
+            // new content so that at least one of them survives.
+            if existed && self.metadata(path).is_err() {
+                return Err(io::Error::new(
+                    e.kind(),
+                    format!("write {path} failed: {e}, new content is kept in {tmp_path}"),
+                ));
+            }
+            return Err(cleanup(e));
         }
 
-        let mut fis = Vec::with_capacity(entries as usize);
+        Ok(())
+    }
 
-        for i in 0..entries {
-            let m = unsafe { Metadata::from(*hfis.offset(i as isize)) };
+    /// Create the parent dir and all it's parent directories of a file.
+    ///
+    /// Nothing will be created if the file doesn't have a parent, like
+    /// `/hello.txt` or `hello.txt`.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use hdrs::{Client, ClientBuilder};
+    ///
+    /// let fs = ClientBuilder::new("default")
+    ///     .with_user("default")
+    ///     .connect()
+    ///     .expect("client connect succeed");
+    /// let _ = fs.create_dir_for_file("/tmp/a/b/c/file.parquet");
+    /// ```
+    pub fn create_dir_for_file(&self, file_path: &str) -> io::Result<()> {
+        match parent_dir(file_path) {
+            Some(dir) => self.create_dir(dir),
+            None => Ok(()),
+        }
+    }
 
-            fis.push(m)
+    /// Check whether the current user can create files in `dir`.
+    ///
+    /// Returns `Ok(false)` if permission is denied, and errors like
+    /// [`NotFound`][io::ErrorKind::NotFound] if `dir` doesn't exist.
+    ///
+    /// # Notes
+    ///
+    /// libhdfs doesn't expose the `checkAccess` RPC, so this creates a
+    /// uniquely named probe file like `dir/._hdrs_probe_<suffix>` and removes
+    /// it immediately. Other clients listing `dir` might observe the probe
+    /// file in between, and it's left behind if the removal fails.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use hdrs::{Client, ClientBuilder};
+    ///
+    /// let fs = ClientBuilder::new("default")
+    ///     .with_user("default")
+    ///     .connect()
+    ///     .expect("client connect succeed");
+    /// let writable = fs.can_write("/tmp/output").expect("check must succeed");
+    /// ```
+    pub fn can_write(&self, dir: &str) -> io::Result<bool> {
+        // Creating a file creates all its parents, make sure dir exists first.
+        if !self.metadata(dir)?.is_dir() {
+            return Err(io::Error::from_raw_os_error(libc::ENOTDIR));
         }
 
-        // Make sure hfis has been freed.
-        unsafe { hdfsFreeFileInfo(hfis, entries) };
+        let probe = match self.create_probe(dir) {
+            Ok(probe) => probe,
+            Err(e) if e.kind() == io::ErrorKind::PermissionDenied => return Ok(false),
+            Err(e) => return Err(e),
+        };
 
-        Ok(fis.into())
+        // The probe was created, a leftover probe file doesn't change that.
+        if let Err(e) = self.remove_file(&probe) {
+            debug!("remove probe file {} failed: {}", probe, e);
+        }
+        Ok(true)
     }
 
-    /// mkdir create dir and all it's parent directories.
+    /// Returns the user that hdfs associates with this client, useful for
+    /// audit logging.
     ///
-    /// The behavior is similar to `mkdir -p /path/to/dir`.
+    /// The user set by [`ClientBuilder::with_user`] is returned as is.
+    /// Otherwise, it's probed by [`Client::effective_user_in`] on the working
+    /// directory, which is the user's home directory like `/user/<name>`.
     ///
     /// # Examples
     ///
@@ -438,19 +2431,145 @@ impl Client {
     ///     .with_user("default")
     ///     .connect()
     ///     .expect("client connect succeed");
-    /// let _ = fs.create_dir("/tmp");
+    /// let user = fs.effective_user().expect("must succeed");
     /// ```
-    pub fn create_dir(&self, path: &str) -> io::Result<()> {
-        let n = unsafe {
-            let p = CString::new(path)?;
-            hdfsCreateDirectory(self.fs, p.as_ptr())
-        };
+    pub fn effective_user(&self) -> io::Result<String> {
+        if let Some(user) = &self.user {
+            return Ok(user.clone());
+        }
 
-        if n == -1 {
+        self.effective_user_in(&self.working_directory()?)
+    }
+
+    /// Returns the user that hdfs associates with this client by probing
+    /// `dir`.
+    ///
+    /// # Notes
+    ///
+    /// libhdfs doesn't expose the current user, so this creates a probe file
+    /// like `<dir>/._hdrs_probe_<suffix>`, reads its owner and removes it.
+    /// The user must be able to write `dir`, failing to remove the probe is
+    /// only logged. For kerberos, the owner is the short name mapped from the
+    /// principal by `hadoop.security.auth_to_local`, like `hdfs` for
+    /// `hdfs/namenode@EXAMPLE.COM`.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use hdrs::{Client, ClientBuilder};
+    ///
+    /// let fs = ClientBuilder::new("default")
+    ///     .connect()
+    ///     .expect("client connect succeed");
+    /// let user = fs.effective_user_in("/tmp").expect("must succeed");
+    /// ```
+    pub fn effective_user_in(&self, dir: &str) -> io::Result<String> {
+        let probe = self.create_probe(dir)?;
+
+        let owner = self.metadata(&probe).map(|m| m.owner().to_string());
+        if let Err(e) = self.remove_file(&probe) {
+            debug!("remove probe file {} failed: {}", probe, e);
+        }
+        owner
+    }
+
+    /// Returns the working directory of this client.
+    fn working_directory(&self) -> io::Result<String> {
+        let mut buf = vec![0; libc::PATH_MAX as usize];
+        let p = unsafe { hdfsGetWorkingDirectory(self.fs, buf.as_mut_ptr(), buf.len()) };
+        if p.is_null() {
             return Err(io::Error::last_os_error());
         }
 
-        Ok(())
+        let dir = unsafe { CStr::from_ptr(p) };
+        Ok(dir.to_string_lossy().into_owned())
+    }
+
+    /// Create an empty probe file with unique name in `dir`, returns its path.
+    fn create_probe(&self, dir: &str) -> io::Result<String> {
+        let probe = format!(
+            "{}/._hdrs_probe_{}",
+            dir.trim_end_matches('/'),
+            tmp_suffix()
+        );
+        debug!("create probe file {}", probe);
+
+        self.open_file()
+            .write(true)
+            .create_new(true)
+            .open(&probe)?
+            .flush_and_close()?;
+        Ok(probe)
+    }
+}
+
+/// FileInfoGuard frees the `hdfsFileInfo` array returned by libhdfs on drop.
+///
+/// Converting entries could panic (for example, on invalid UTF-8 names) and
+/// unwind through the caller, the guard makes sure the array won't be leaked.
+struct FileInfoGuard {
+    ptr: *mut hdfsFileInfo,
+    entries: i32,
+}
+
+impl Drop for FileInfoGuard {
+    fn drop(&mut self) {
+        unsafe { hdfsFreeFileInfo(self.ptr, self.entries) };
+    }
+}
+
+/// Check that `mode` only contains permission bits, including sticky bit.
+fn check_mode(mode: i16) -> io::Result<()> {
+    if !(0..=0o7777).contains(&mode) {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("permission mode {mode:o} is out of range 0..=0o7777"),
+        ));
+    }
+    Ok(())
+}
+
+fn already_exists(path: &str) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::AlreadyExists,
+        format!("cannot rename to {path}: destination already exists"),
+    )
+}
+
+/// Returns the error of operations not exposed by libhdfs, see
+/// "Unsupported operations" in docs of [`Client`].
+fn unsupported(op: &str) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::Unsupported,
+        format!("libhdfs doesn't support {op}, see docs of `hdrs::Client` for alternatives"),
+    )
+}
+
+/// Returns a suffix for temp files that is unique across processes.
+fn tmp_suffix() -> String {
+    static COUNTER: AtomicU64 = AtomicU64::new(0);
+
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_nanos())
+        .unwrap_or_default();
+    format!(
+        "{:x}-{:x}-{:x}",
+        std::process::id(),
+        nanos,
+        COUNTER.fetch_add(1, Ordering::Relaxed)
+    )
+}
+
+/// Chunk size used by [`Client::verify_copy`], 8 MiB.
+const VERIFY_CHUNK_SIZE: usize = 8 * 1024 * 1024;
+
+/// Returns the parent dir of given path, `None` if there is no parent to create.
+fn parent_dir(path: &str) -> Option<&str> {
+    let path = path.trim_end_matches('/');
+    match path.rfind('/') {
+        None | Some(0) => None,
+        Some(idx) => Some(&path[..idx]),
     }
 }
 
@@ -460,7 +2579,7 @@ mod tests {
 
     use log::debug;
 
-    use crate::client::ClientBuilder;
+    use crate::client::{check_mode, parent_dir, ClientBuilder};
 
     #[test]
     fn test_client_connect() {
@@ -512,7 +2631,21 @@ mod tests {
 
         let f = fs.read_dir("/tmp").expect("open file success");
         debug!("Metadata: {:?}", f);
-        assert!(f.len() > 0)
+        assert!(!f.is_empty())
+    }
+
+    #[test]
+    fn test_client_readdir_light() {
+        let _ = env_logger::try_init();
+
+        let fs = ClientBuilder::new("default")
+            .connect()
+            .expect("init success");
+        debug!("Client: {:?}", fs);
+
+        let entries = fs.read_dir_light("/tmp").expect("read dir success");
+        debug!("DirEntry: {:?}", entries);
+        assert!(!entries.is_empty())
     }
 
     #[test]
@@ -527,4 +2660,35 @@ mod tests {
         fs.create_dir("/tmp")
             .expect("mkdir on exist dir should succeed");
     }
+
+    #[test]
+    fn test_check_mode() {
+        for mode in [0, 0o640, 0o755, 0o1777, 0o7777] {
+            assert!(check_mode(mode).is_ok(), "mode {mode:o}");
+        }
+        for mode in [-1, 0o10000, i16::MAX] {
+            let err = check_mode(mode).unwrap_err();
+            assert_eq!(
+                err.kind(),
+                std::io::ErrorKind::InvalidInput,
+                "mode {mode:o}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_parent_dir() {
+        let cases = vec![
+            ("/a/b/c/file.parquet", Some("/a/b/c")),
+            ("/a/b/", Some("/a")),
+            ("a/file", Some("a")),
+            ("/file", None),
+            ("file", None),
+            ("/", None),
+        ];
+
+        for (path, expect) in cases {
+            assert_eq!(parent_dir(path), expect, "parent of {path}");
+        }
+    }
 }
@@ -0,0 +1,118 @@
+use std::io::{Error, ErrorKind, Result};
+
+/// Parse properties from hadoop's XML configuration like `core-site.xml`.
+///
+/// Only the standard structure is supported:
+///
+/// ```xml
+/// <configuration>
+///   <property>
+///     <name>fs.defaultFS</name>
+///     <value>hdfs://127.0.0.1:9000</value>
+///   </property>
+/// </configuration>
+/// ```
+pub(crate) fn parse_xml(content: &str) -> Result<Vec<(String, String)>> {
+    let content = strip_comments(content)?;
+
+    let mut properties = Vec::new();
+    let mut rest = content.as_str();
+    while let Some(start) = rest.find("<property>") {
+        let end = rest[start..]
+            .find("</property>")
+            .ok_or_else(|| invalid("`<property>` is not closed"))?;
+        let property = &rest[start + "<property>".len()..start + end];
+
+        let name = element(property, "name")?
+            .ok_or_else(|| invalid("`<property>` doesn't have `<name>`"))?;
+        // Hadoop treats missing value as empty string.
+        let value = element(property, "value")?.unwrap_or_default();
+        properties.push((name, value));
+
+        rest = &rest[start + end + "</property>".len()..];
+    }
+
+    Ok(properties)
+}
+
+fn invalid(msg: &str) -> Error {
+    Error::new(ErrorKind::InvalidData, format!("invalid config xml: {msg}"))
+}
+
+fn strip_comments(content: &str) -> Result<String> {
+    let mut s = String::with_capacity(content.len());
+    let mut rest = content;
+    while let Some(start) = rest.find("<!--") {
+        s.push_str(&rest[..start]);
+        let end = rest[start..]
+            .find("-->")
+            .ok_or_else(|| invalid("comment is not closed"))?;
+        rest = &rest[start + end + "-->".len()..];
+    }
+    s.push_str(rest);
+    Ok(s)
+}
+
+/// Returns the unescaped and trimmed text of element `tag`.
+fn element(content: &str, tag: &str) -> Result<Option<String>> {
+    let (open, close) = (format!("<{tag}>"), format!("</{tag}>"));
+    let start = match content.find(&open) {
+        None => return Ok(None),
+        Some(idx) => idx + open.len(),
+    };
+    let end = content[start..]
+        .find(&close)
+        .ok_or_else(|| invalid(&format!("`{open}` is not closed")))?;
+
+    Ok(Some(unescape(content[start..start + end].trim())))
+}
+
+fn unescape(s: &str) -> String {
+    s.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_xml() -> anyhow::Result<()> {
+        let content = r#"<?xml version="1.0"?>
+<?xml-stylesheet type="text/xsl" href="configuration.xsl"?>
+<configuration>
+  <!-- <property><name>commented</name><value>out</value></property> -->
+  <property>
+    <name>fs.defaultFS</name>
+    <value>hdfs://127.0.0.1:9000</value>
+    <final>true</final>
+  </property>
+  <property>
+    <name>dfs.client.use.datanode.hostname</name>
+    <description>a &amp; b</description>
+    <value> a&amp;b&lt;c </value>
+  </property>
+  <property>
+    <name>empty</name>
+  </property>
+</configuration>
+"#;
+
+        assert_eq!(
+            parse_xml(content)?,
+            vec![
+                ("fs.defaultFS".into(), "hdfs://127.0.0.1:9000".into()),
+                ("dfs.client.use.datanode.hostname".into(), "a&b<c".into()),
+                ("empty".into(), "".into()),
+            ]
+        );
+
+        assert!(parse_xml("<property><value>x</value></property>").is_err());
+        assert!(parse_xml("<property><name>x</name>").is_err());
+
+        Ok(())
+    }
+}
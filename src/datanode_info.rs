@@ -0,0 +1,31 @@
+/// DatanodeState is the admin state of a datanode in [`DatanodeInfo`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DatanodeState {
+    /// The datanode is alive and serving.
+    Live,
+    /// The datanode stopped sending heartbeats to namenode.
+    Dead,
+    /// Blocks on the datanode are being replicated to others before it's
+    /// removed from the cluster.
+    Decommissioning,
+    /// The datanode has been decommissioned.
+    Decommissioned,
+}
+
+/// DatanodeInfo describes a datanode of the cluster, returned by
+/// [`datanode_report`][crate::Client::datanode_report].
+///
+/// The fields mirror `hdfs dfsadmin -report`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DatanodeInfo {
+    /// Hostname of the datanode, like `dn1.example.com`.
+    pub hostname: String,
+    /// Raw capacity configured for hdfs in bytes.
+    pub capacity: u64,
+    /// Raw bytes used by hdfs, including all replicas.
+    pub used: u64,
+    /// Raw bytes available for hdfs.
+    pub remaining: u64,
+    /// The state of the datanode.
+    pub state: DatanodeState,
+}
@@ -0,0 +1,88 @@
+use std::collections::HashMap;
+
+use crate::Metadata;
+
+/// DirDiff is the difference between two listings of a dir, returned by
+/// [`diff_dir`][crate::Client::diff_dir].
+///
+/// Entries are matched by path. An entry is considered modified if its size
+/// or modification time changed.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DirDiff {
+    /// Entries that exist now but not in the previous listing.
+    pub added: Vec<Metadata>,
+    /// Entries that existed in the previous listing but not now.
+    pub removed: Vec<Metadata>,
+    /// Entries that exist in both listings but changed, with current metadata.
+    pub modified: Vec<Metadata>,
+}
+
+impl DirDiff {
+    pub(crate) fn new(current: Vec<Metadata>, previous: &[Metadata]) -> Self {
+        let mut previous: HashMap<&str, &Metadata> =
+            previous.iter().map(|m| (m.path(), m)).collect();
+
+        let mut diff = DirDiff::default();
+        for m in current {
+            match previous.remove(m.path()) {
+                None => diff.added.push(m),
+                Some(prev) => {
+                    if prev.len() != m.len() || prev.modified() != m.modified() {
+                        diff.modified.push(m)
+                    }
+                }
+            }
+        }
+        diff.removed = previous.into_values().cloned().collect();
+        diff.removed.sort_by(|a, b| a.path().cmp(b.path()));
+
+        diff
+    }
+
+    /// Returns `true` if nothing changed.
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.modified.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, UNIX_EPOCH};
+
+    use super::*;
+
+    fn meta(path: &str, len: u64, modified: u64) -> Metadata {
+        Metadata::builder()
+            .with_path(path)
+            .with_len(len)
+            .with_modified(UNIX_EPOCH + Duration::from_secs(modified))
+            .build()
+    }
+
+    #[test]
+    fn test_dir_diff() {
+        let previous = vec![
+            meta("/a/same", 1, 1),
+            meta("/a/resized", 1, 1),
+            meta("/a/touched", 1, 1),
+            meta("/a/removed", 1, 1),
+        ];
+        let current = vec![
+            meta("/a/same", 1, 1),
+            meta("/a/resized", 2, 1),
+            meta("/a/touched", 1, 2),
+            meta("/a/added", 1, 1),
+        ];
+
+        let diff = DirDiff::new(current, &previous);
+        assert_eq!(diff.added, vec![meta("/a/added", 1, 1)]);
+        assert_eq!(diff.removed, vec![meta("/a/removed", 1, 1)]);
+        assert_eq!(
+            diff.modified,
+            vec![meta("/a/resized", 2, 1), meta("/a/touched", 1, 2)]
+        );
+
+        let diff = DirDiff::new(previous.clone(), &previous);
+        assert!(diff.is_empty());
+    }
+}
@@ -0,0 +1,114 @@
+use std::ffi::CStr;
+use std::io;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use hdfs_sys::*;
+
+use crate::metadata::parse_path;
+use crate::{Client, Metadata};
+
+/// DirEntry is a lightweight entry returned by [`read_dir_light`][crate::Client::read_dir_light].
+///
+/// Only path, size, kind and modification time are decoded. Call
+/// [`DirEntry::metadata`] to fetch the full [`Metadata`].
+#[derive(Debug, Clone)]
+pub struct DirEntry {
+    client: Arc<Client>,
+
+    /// the path of the file, like `/path/to/file`
+    path: String,
+    /// the size of the file in bytes
+    size: i64,
+    /// file or directory
+    kind: u32,
+    /// the last modification time for the file in seconds
+    last_mod: i64,
+}
+
+impl DirEntry {
+    pub(crate) fn new(client: Arc<Client>, hfi: &hdfsFileInfo) -> Self {
+        DirEntry {
+            client,
+            // HDFS allows names that are not valid utf-8, replace the invalid
+            // bytes instead of failing the whole listing.
+            path: parse_path(&unsafe { CStr::from_ptr(hfi.mName) }.to_string_lossy()),
+            size: hfi.mSize,
+            kind: hfi.mKind,
+            last_mod: hfi.mLastMod,
+        }
+    }
+
+    /// the path of the file, like `/path/to/file`
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+
+    /// the size of the file in bytes
+    pub fn len(&self) -> u64 {
+        self.size as u64
+    }
+
+    /// the file is zero bytes
+    ///
+    /// Directories are always zero-length on HDFS, so this will always be
+    /// `true` for them. Combine with [`DirEntry::is_file`] to detect empty files.
+    pub fn is_empty(&self) -> bool {
+        self.size == 0
+    }
+
+    /// file or directory
+    pub fn is_dir(&self) -> bool {
+        self.kind == tObjectKind_kObjectKindDirectory
+    }
+
+    /// file or directory
+    pub fn is_file(&self) -> bool {
+        self.kind == tObjectKind_kObjectKindFile
+    }
+
+    /// the last modification time for the file in seconds
+    pub fn modified(&self) -> SystemTime {
+        UNIX_EPOCH
+            .checked_add(Duration::from_secs(self.last_mod as u64))
+            .expect("must be valid SystemTime")
+    }
+
+    /// Stat this entry again to get the full metadata.
+    ///
+    /// It uses the operation timeout of the client that listed this entry.
+    pub fn metadata(&self) -> io::Result<Metadata> {
+        self.client.metadata(&self.path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::ffi::CString;
+
+    use super::*;
+
+    #[test]
+    fn test_dir_entry_non_utf8_name() -> anyhow::Result<()> {
+        let hfi = hdfsFileInfo {
+            mKind: tObjectKind_kObjectKindFile,
+            mName: CString::new(b"/path/to/\xff".to_vec())?.into_raw(),
+            mLastMod: 0,
+            mSize: 0,
+            mReplication: 0,
+            mBlockSize: 0,
+            mOwner: std::ptr::null_mut(),
+            mGroup: std::ptr::null_mut(),
+            mPermissions: 0,
+            mLastAccess: 0,
+        };
+
+        let entry = DirEntry::new(Arc::new(Client::new(std::ptr::null_mut())), &hfi);
+        assert_eq!(entry.path(), "/path/to/\u{fffd}");
+        assert!(entry.is_file());
+        assert!(entry.is_empty());
+
+        drop(unsafe { CString::from_raw(hfi.mName) });
+        Ok(())
+    }
+}
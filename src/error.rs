@@ -0,0 +1,168 @@
+use std::fmt;
+use std::io::{Error, ErrorKind};
+
+/// The errno set by libhdfs for Java exceptions without a specific mapping,
+/// like `BlockMissingException` or `SocketTimeoutException`.
+const EINTERNAL: i32 = 255;
+
+/// Returns `true` if the error is likely transient and the operation could
+/// be retried on the same file, like failures talking to datanodes.
+///
+/// libhdfs converts Java exceptions into errno, so errors are classified by
+/// it:
+///
+/// - Retryable: `EIO` and `EINTERNAL` (`255`) which libhdfs sets for
+///   network and datanode failures, `EINTR`, `ETIMEDOUT`, `ECONNREFUSED`,
+///   `EAGAIN`, and [`ErrorKind::Interrupted`]/[`ErrorKind::TimedOut`].
+/// - Permanent: everything else, like [`ErrorKind::NotFound`] if the file
+///   has been deleted or [`ErrorKind::PermissionDenied`].
+///
+/// [`ErrorKind::ConnectionReset`] returned for a stale filesystem is
+/// permanent as well: retrying on the same file won't help, callers should
+/// reconnect and reopen the file instead.
+///
+/// # Examples
+///
+/// ```no_run
+/// use std::io::Read;
+///
+/// use hdrs::{Client, ClientBuilder};
+///
+/// let fs = ClientBuilder::new("default")
+///     .with_user("default")
+///     .connect()
+///     .expect("client connect succeed");
+/// let mut f = fs
+///     .open_file()
+///     .read(true)
+///     .open("/tmp/hello.txt")
+///     .expect("must open success");
+///
+/// let mut buf = vec![0; 1024];
+/// let n = loop {
+///     match f.read(&mut buf) {
+///         Err(e) if hdrs::is_retryable(&e) => continue,
+///         v => break v.expect("read must succeed"),
+///     }
+/// };
+/// ```
+pub fn is_retryable(err: &Error) -> bool {
+    // Classify the wrapped error instead, like `WriteAllFromError`.
+    let inner = err
+        .get_ref()
+        .and_then(|e| e.source())
+        .and_then(|e| e.downcast_ref::<Error>());
+    if let Some(inner) = inner {
+        return is_retryable(inner);
+    }
+
+    if let Some(code) = err.raw_os_error() {
+        return matches!(
+            code,
+            libc::EIO
+                | EINTERNAL
+                | libc::EINTR
+                | libc::ETIMEDOUT
+                | libc::ECONNREFUSED
+                | libc::EAGAIN
+        );
+    }
+
+    matches!(err.kind(), ErrorKind::Interrupted | ErrorKind::TimedOut)
+}
+
+/// WriteAllFromError is returned by [`File::write_all_from`][crate::File::write_all_from]
+/// on failure, wrapped in an [`Error`] with the kind of the failed call.
+///
+/// # Examples
+///
+/// ```no_run
+/// use hdrs::{Client, ClientBuilder, WriteAllFromError};
+///
+/// let fs = ClientBuilder::new("default")
+///     .with_user("default")
+///     .connect()
+///     .expect("client connect succeed");
+/// let mut f = fs
+///     .open_file()
+///     .write(true)
+///     .create(true)
+///     .open("/tmp/events.log")
+///     .expect("must open success");
+///
+/// if let Err(e) = f.write_all_from(&[b"event-1\n", b"event-2\n"]) {
+///     let written = e
+///         .get_ref()
+///         .and_then(|e| e.downcast_ref::<WriteAllFromError>())
+///         .map(|e| e.written());
+///     println!("{written:?} bytes written before failure: {e}");
+/// }
+/// ```
+#[derive(Debug)]
+pub struct WriteAllFromError {
+    written: u64,
+    source: Error,
+}
+
+impl WriteAllFromError {
+    pub(crate) fn new(written: u64, source: Error) -> Self {
+        Self { written, source }
+    }
+
+    /// Bytes of the batch that have been written to hdfs before the failure.
+    pub fn written(&self) -> u64 {
+        self.written
+    }
+}
+
+impl fmt::Display for WriteAllFromError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "write failed after {} bytes written: {}",
+            self.written, self.source
+        )
+    }
+}
+
+impl std::error::Error for WriteAllFromError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(&self.source)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_retryable() {
+        let cases = vec![
+            (Error::from_raw_os_error(libc::EIO), true),
+            (Error::from_raw_os_error(EINTERNAL), true),
+            (Error::from_raw_os_error(libc::ETIMEDOUT), true),
+            (Error::from_raw_os_error(libc::EINTR), true),
+            (Error::from_raw_os_error(libc::ENOENT), false),
+            (Error::from_raw_os_error(libc::EACCES), false),
+            (Error::from_raw_os_error(libc::EINVAL), false),
+            (Error::new(ErrorKind::TimedOut, "operation timed out"), true),
+            (Error::new(ErrorKind::ConnectionReset, "stale"), false),
+            (Error::new(ErrorKind::UnexpectedEof, "eof"), false),
+        ];
+
+        for (err, expect) in cases {
+            assert_eq!(is_retryable(&err), expect, "{err}");
+        }
+    }
+
+    #[test]
+    fn test_is_retryable_write_all_from() {
+        for (source, expect) in [
+            (Error::from_raw_os_error(libc::EIO), true),
+            (Error::from_raw_os_error(libc::EACCES), false),
+        ] {
+            let err = Error::new(source.kind(), WriteAllFromError::new(13, source));
+            assert_eq!(is_retryable(&err), expect, "{err}");
+        }
+    }
+}
@@ -0,0 +1,26 @@
+use std::num::NonZeroUsize;
+
+/// Set the max number of threads used by async operations.
+///
+/// [`AsyncFile`][crate::AsyncFile] and other async operations perform blocking hdfs
+/// calls on the global thread pool provided by the [`blocking`] crate. The pool
+/// spawns at most `500` threads by default, which can oversubscribe the NameNode
+/// while thousands of operations run concurrently.
+///
+/// # Notes
+///
+/// The thread pool is process-wide and shared with every crate that depends on
+/// [`blocking`] (like `async-fs`), so this setting affects them too. The default
+/// can also be changed by setting the `BLOCKING_MAX_THREADS` environment variable
+/// before the first async operation.
+///
+/// # Examples
+///
+/// ```no_run
+/// use std::num::NonZeroUsize;
+///
+/// hdrs::set_max_blocking_threads(NonZeroUsize::new(64).unwrap());
+/// ```
+pub fn set_max_blocking_threads(threads: NonZeroUsize) {
+    blocking::set_max_blocking_threads(threads)
+}
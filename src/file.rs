@@ -1,19 +1,128 @@
-use std::io::{Error, ErrorKind, Read, Result, Seek, SeekFrom, Write};
+use std::io::{BufReader, Error, ErrorKind, IoSliceMut, Read, Result, Seek, SeekFrom, Write};
 use std::ptr;
+use std::sync::atomic::{AtomicI64, Ordering};
+use std::sync::Mutex;
 
 use hdfs_sys::*;
 use libc::c_void;
 use log::debug;
 
-use crate::Client;
+use crate::{Client, Metadata, RangeReader, WriteAllFromError};
 
 // at most 2^30 bytes, ~1GB
 const FILE_LIMIT: usize = 1073741824;
+// FILE_LIMIT must fit in `tSize` (i32) so that `len_limit` never overflows.
+const _: () = assert!(FILE_LIMIT <= i32::MAX as usize);
+
+/// Compute the absolute offset of `base + n` for seek.
+///
+/// Returns [`ErrorKind::InvalidInput`] if the offset is negative or overflowing,
+/// the same as `std::fs::File`.
+fn seek_offset(base: i64, n: i64) -> Result<u64> {
+    match base.checked_add(n) {
+        Some(offset) if offset >= 0 => Ok(offset as u64),
+        _ => Err(invalid_seek()),
+    }
+}
+
+fn invalid_seek() -> Error {
+    Error::new(
+        ErrorKind::InvalidInput,
+        "invalid seek to a negative or overflowing position",
+    )
+}
+
+/// Buffer capacity used by [`File::buffered`], 1 MiB.
+const BUFFERED_CAPACITY: usize = 1024 * 1024;
+
+/// Compute the buffer size used by [`File::write_from`] from the block size.
+///
+/// Falls back to [`BUFFERED_CAPACITY`] if the block size is unknown, and
+/// clamps to [`FILE_LIMIT`] so that a single `hdfsWrite` can take it.
+fn write_buffer_size(block_size: i64) -> usize {
+    if block_size <= 0 {
+        return BUFFERED_CAPACITY;
+    }
+    (block_size as u64).min(FILE_LIMIT as u64) as usize
+}
+
+/// Max retry times on interrupted reads.
+const READ_RETRY_TIMES: usize = 3;
+
+/// Retry `f` while it returns [`ErrorKind::Interrupted`], at most [`READ_RETRY_TIMES`] times.
+///
+/// JVM under GC pressure could interrupt `hdfsRead` which should be retried
+/// instead of propagated. Other errors and EOF (`Ok(0)`) are returned directly.
+fn retry_interrupted(mut f: impl FnMut() -> Result<usize>) -> Result<usize> {
+    let mut retries = 0;
+    loop {
+        match f() {
+            Err(e) if e.kind() == ErrorKind::Interrupted && retries < READ_RETRY_TIMES => {
+                retries += 1;
+                debug!("read interrupted, retry {retries} times: {e}");
+            }
+            v => return v,
+        }
+    }
+}
+
+/// Clamp the length of buffer to the max size that `hdfsRead`/`hdfsWrite` can take.
+///
+/// Callers like `write_all` will loop on short read/write, so buffers larger
+/// than `i32::MAX` will be handled in multiple calls.
+fn len_limit(buf_len: usize) -> i32 {
+    buf_len.min(FILE_LIMIT) as i32
+}
+
+/// FlushMode decides what [`Write::flush`] on [`File`] does, set by
+/// [`OpenOptions::with_flush_mode`][crate::OpenOptions::with_flush_mode].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum FlushMode {
+    /// Flush client side buffers via `hdfsFlush`, the cheapest one.
+    #[default]
+    Buffer,
+    /// Make written data visible to new readers via `hdfsHFlush`.
+    Visible,
+    /// Persist written data to datanodes' disks via `hdfsHSync`, the most
+    /// expensive one, useful for write-ahead logs.
+    Durable,
+}
 
 /// File will hold the underlying pointer to `hdfsFile`.
 ///
 /// The internal file will be closed while `Drop`, so their is no need to close it manually.
 ///
+/// # Flush on close
+///
+/// `hdfsCloseFile` closes the underlying java output stream, which flushes all buffered
+/// data to datanodes and completes the file on namenode. This holds for all libhdfs
+/// versions supported by hdfs-sys. Data written via `&File` is also covered since it
+/// shares the same handle with the owning `File`.
+///
+/// However, errors returned by `hdfsCloseFile` are ignored while `Drop`. Use
+/// [`File::flush_and_close`] if you need to make sure data has been persisted.
+///
+/// # Stale filesystem
+///
+/// If the filesystem handle has been closed, read and write will return
+/// [`ErrorKind::ConnectionReset`]. Callers should reconnect and reopen the file.
+/// There is no auto-reconnect: reads could be resumed at the last known position,
+/// but data written to a closed stream may have been lost and must be rewritten
+/// by the caller.
+///
+/// # Buffered reads
+///
+/// `File` doesn't buffer reads, every `read` is a JNI call. Avoid [`Read::bytes`]
+/// on `File` directly, use [`File::buffered`] instead.
+///
+/// # Interrupted reads
+///
+/// Reads that failed with `EINTR` will be retried at most `3` times before
+/// returning [`ErrorKind::Interrupted`], EOF is still returned as `Ok(0)`.
+///
+/// Use [`is_retryable`][crate::is_retryable] to tell transient failures like
+/// datanode timeouts apart from permanent ones like the file being deleted.
+///
 /// # Examples
 ///
 /// ```no_run
@@ -35,6 +144,20 @@ pub struct File {
     fs: hdfsFS,
     f: hdfsFile,
     path: String,
+    flush_mode: FlushMode,
+    /// Whether to `hdfsHSync` after every write.
+    sync_on_write: bool,
+    /// Error of the failed `hdfsHSync` after a write, returned by the next
+    /// write, flush or sync.
+    sync_error: Mutex<Option<Error>>,
+    /// Max bytes of a single `hdfsRead`/`hdfsPread`.
+    max_read_chunk: usize,
+    /// Cached result of `hdfsFileIsOpenForWrite`, hdfs files are either
+    /// opened for read or for write.
+    writable: bool,
+    /// Client side position of the cursor, updated on every read, write and
+    /// seek so that `SeekFrom::Current` doesn't need `hdfsTell`.
+    pos: AtomicI64,
 }
 
 /// HDFS's client handle is thread safe.
@@ -43,6 +166,11 @@ unsafe impl Sync for File {}
 
 impl Drop for File {
     fn drop(&mut self) {
+        // File has been closed by `flush_and_close`.
+        if self.f.is_null() {
+            return;
+        }
+
         unsafe {
             debug!("file has been closed");
             let _ = hdfsCloseFile(self.fs, self.f);
@@ -53,25 +181,224 @@ impl Drop for File {
 }
 
 impl File {
-    pub(crate) fn new(fs: hdfsFS, f: hdfsFile, path: &str) -> Self {
+    pub(crate) fn new(
+        fs: hdfsFS,
+        f: hdfsFile,
+        path: &str,
+        flush_mode: FlushMode,
+        sync_on_write: bool,
+        max_read_chunk: usize,
+    ) -> Self {
         File {
             fs,
             f,
             path: path.to_string(),
+            flush_mode,
+            sync_on_write,
+            sync_error: Mutex::new(None),
+            max_read_chunk: match max_read_chunk {
+                0 => FILE_LIMIT,
+                v => v.min(FILE_LIMIT),
+            },
+            writable: unsafe { hdfsFileIsOpenForWrite(f) == 1 },
+            // Files opened for append start at the end.
+            pos: AtomicI64::new(unsafe { hdfsTell(fs, f) }.max(0)),
         }
     }
 
+    /// The path used to open this file.
+    pub(crate) fn path(&self) -> &str {
+        &self.path
+    }
+
+    /// Returns the [`FlushMode`] used by [`Write::flush`] on this file.
+    pub fn flush_mode(&self) -> FlushMode {
+        self.flush_mode
+    }
+
+    /// Returns `true` if the file is opened for write.
+    pub(crate) fn is_open_for_write(&self) -> bool {
+        self.writable
+    }
+
+    /// Returns a clear error instead of the opaque one from hdfs if the
+    /// file is not opened for read.
+    fn check_readable(&self) -> Result<()> {
+        if self.writable {
+            return Err(Error::new(
+                ErrorKind::PermissionDenied,
+                format!("file {} is not opened for reading", self.path),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Returns a clear error instead of the opaque one from hdfs if the
+    /// file is not opened for write.
+    fn check_writable(&self) -> Result<()> {
+        if !self.writable {
+            return Err(Error::new(
+                ErrorKind::PermissionDenied,
+                format!("file {} is not opened for writing", self.path),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Stat the file to get its metadata.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use hdrs::{Client, ClientBuilder};
+    ///
+    /// let fs = ClientBuilder::new("default")
+    ///     .with_user("default")
+    ///     .connect()
+    ///     .expect("client connect succeed");
+    /// let f = fs
+    ///     .open_file()
+    ///     .read(true)
+    ///     .open("/tmp/hello.txt")
+    ///     .expect("must open success");
+    /// let size = f.metadata().expect("must stat success").len();
+    /// ```
+    pub fn metadata(&self) -> Result<Metadata> {
+        Client::new(self.fs).metadata(&self.path)
+    }
+
+    /// Try to lock the file for exclusive write.
+    ///
+    /// HDFS doesn't support advisory locks like POSIX `flock`. Instead, namenode
+    /// grants a single-writer lease to the client that opens a file for write,
+    /// and other writers will fail to open the file until the lease is released
+    /// by closing the file or expired. So opening for write IS the lock.
+    ///
+    /// Returns `Ok(true)` if the file is opened for write, which means we hold
+    /// the lease. Returns [`Unsupported`][ErrorKind::Unsupported] for files opened
+    /// for read since there is no lock for readers.
+    ///
+    /// There is no `unlock`: the lease will be released while the file is closed.
+    pub fn try_lock(&self) -> Result<bool> {
+        if self.is_open_for_write() {
+            return Ok(true);
+        }
+
+        Err(Error::new(
+            ErrorKind::Unsupported,
+            "hdfs doesn't support locking, open the file for write to hold the single-writer lease instead",
+        ))
+    }
+
+    /// Sync written data to the disks of datanodes via `hdfsHSync`, no
+    /// matter what the [`FlushMode`] is.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use std::io::Write;
+    ///
+    /// use hdrs::{Client, ClientBuilder};
+    ///
+    /// let fs = ClientBuilder::new("default")
+    ///     .with_user("default")
+    ///     .connect()
+    ///     .expect("client connect succeed");
+    /// let mut f = fs
+    ///     .open_file()
+    ///     .write(true)
+    ///     .create(true)
+    ///     .open("/tmp/hello.txt")
+    ///     .expect("must open success");
+    /// f.write_all(b"Hello, World!").expect("must write success");
+    /// f.sync_all().expect("must sync success");
+    /// ```
+    pub fn sync_all(&self) -> Result<()> {
+        self.check_writable()?;
+        self.take_sync_error()?;
+        self.inner_sync()
+    }
+
+    /// Flush and close the file, returning the first error that happens.
+    ///
+    /// The file will be closed even if flush failed.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use std::io::Write;
+    ///
+    /// use hdrs::{Client, ClientBuilder};
+    ///
+    /// let fs = ClientBuilder::new("default")
+    ///     .with_user("default")
+    ///     .connect()
+    ///     .expect("client connect succeed");
+    /// let mut f = fs
+    ///     .open_file()
+    ///     .write(true)
+    ///     .create(true)
+    ///     .open("/tmp/hello.txt")
+    ///     .expect("must open success");
+    /// f.write_all(b"Hello, World!").expect("must write success");
+    /// f.flush_and_close().expect("must close success");
+    /// ```
+    pub fn flush_and_close(mut self) -> Result<()> {
+        let flushed = if self.is_open_for_write() {
+            self.flush()
+        } else {
+            Ok(())
+        };
+
+        debug!("file {} has been closed", self.path);
+        let n = unsafe { hdfsCloseFile(self.fs, self.f) };
+        // hdfsCloseFile will free self.f no matter success or failed.
+        self.f = ptr::null_mut();
+
+        flushed?;
+        if n == -1 {
+            return Err(Error::last_os_error());
+        }
+
+        Ok(())
+    }
+
     /// Works only for files opened in read-only mode.
+    ///
+    /// Files opened for write can only "seek" to their current position,
+    /// which keeps [`Seek::stream_position`] working.
     fn inner_seek(&self, offset: i64) -> Result<()> {
+        if self.writable {
+            if offset == self.position() {
+                return Ok(());
+            }
+            return Err(Error::new(
+                ErrorKind::Unsupported,
+                format!(
+                    "file {} is opened for writing and can't seek, hdfs only supports sequential writes",
+                    self.path
+                ),
+            ));
+        }
+
         let n = unsafe { hdfsSeek(self.fs, self.f, offset) };
 
         if n == -1 {
             return Err(Error::last_os_error());
         }
 
+        self.pos.store(offset, Ordering::Relaxed);
         Ok(())
     }
 
+    /// Returns the client side position of the cursor.
+    fn position(&self) -> i64 {
+        self.pos.load(Ordering::Relaxed)
+    }
+
+    /// Returns the position held by the local stream, used to validate
+    /// [`File::position`] in tests.
+    #[cfg(test)]
     fn tell(&self) -> Result<i64> {
         let n = unsafe { hdfsTell(self.fs, self.f) };
 
@@ -82,87 +409,119 @@ impl File {
         Ok(n)
     }
 
-    pub fn read_at(&self, buf: &mut [u8], offset: u64) -> Result<usize> {
+    fn inner_read(&self, buf: &mut [u8]) -> Result<usize> {
+        self.check_readable()?;
+
         let n = unsafe {
-            hdfsPread(
+            hdfsRead(
                 self.fs,
                 self.f,
-                offset as i64,
                 buf.as_ptr() as *mut c_void,
-                buf.len().min(FILE_LIMIT) as i32,
+                len_limit(buf.len().min(self.max_read_chunk)),
             )
         };
 
         if n == -1 {
-            return Err(Error::last_os_error());
+            return Err(self.check_stale(Error::last_os_error()));
         }
 
+        self.pos.fetch_add(n as i64, Ordering::Relaxed);
         Ok(n as usize)
     }
-}
 
-impl Read for File {
-    fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
+    /// Read into each non-empty buffer in order with one `hdfsRead` call per
+    /// buffer, stops at EOF or a short read.
+    ///
+    /// Errors after some bytes have been read are dropped so that the read
+    /// bytes are not lost, the error will be returned by the next read.
+    fn inner_read_vectored(&self, bufs: &mut [IoSliceMut<'_>]) -> Result<usize> {
+        let mut total = 0;
+        for buf in bufs.iter_mut().filter(|buf| !buf.is_empty()) {
+            let n = match retry_interrupted(|| self.inner_read(buf)) {
+                Ok(n) => n,
+                Err(_) if total > 0 => break,
+                Err(e) => return Err(e),
+            };
+            total += n;
+            if n < buf.len() {
+                break;
+            }
+        }
+
+        Ok(total)
+    }
+
+    fn inner_write(&self, buf: &[u8]) -> Result<usize> {
+        self.check_writable()?;
+        self.take_sync_error()?;
+
         let n = unsafe {
-            hdfsRead(
+            hdfsWrite(
                 self.fs,
                 self.f,
-                buf.as_ptr() as *mut c_void,
-                buf.len().min(FILE_LIMIT) as i32,
+                buf.as_ptr() as *const c_void,
+                len_limit(buf.len()),
             )
         };
 
         if n == -1 {
-            return Err(Error::last_os_error());
+            return Err(self.check_stale(Error::last_os_error()));
         }
 
+        self.pos.fetch_add(n as i64, Ordering::Relaxed);
+
+        // The bytes have been written, returning the sync error here would
+        // make callers like `write_all` write them again.
+        if self.sync_on_write && n > 0 {
+            if let Err(e) = self.inner_sync() {
+                debug!("sync file {} after write failed: {}", self.path, e);
+                *self
+                    .sync_error
+                    .lock()
+                    .expect("sync error lock must not be poisoned") = Some(e);
+            }
+        }
         Ok(n as usize)
     }
-}
 
-impl Seek for File {
-    fn seek(&mut self, pos: SeekFrom) -> Result<u64> {
-        match pos {
-            SeekFrom::Start(n) => {
-                self.inner_seek(n as i64)?;
-                Ok(n)
-            }
-            SeekFrom::Current(n) => {
-                let current = self.tell()?;
-                let offset = (current + n) as u64;
-                self.inner_seek(offset as i64)?;
-                Ok(offset)
-            }
-            SeekFrom::End(n) => {
-                let meta = Client::new(self.fs).metadata(&self.path)?;
-                let offset = meta.len() as i64 + n;
-                self.inner_seek(offset)?;
-                Ok(offset as u64)
-            }
+    /// Return the error of the failed sync after a previous write if any.
+    fn take_sync_error(&self) -> Result<()> {
+        match self
+            .sync_error
+            .lock()
+            .expect("sync error lock must not be poisoned")
+            .take()
+        {
+            Some(e) => Err(e),
+            None => Ok(()),
         }
     }
-}
 
-impl Write for File {
-    fn write(&mut self, buf: &[u8]) -> Result<usize> {
-        let n = unsafe {
-            hdfsWrite(
-                self.fs,
-                self.f,
-                buf.as_ptr() as *const c_void,
-                buf.len().min(FILE_LIMIT) as i32,
-            )
-        };
+    fn inner_sync(&self) -> Result<()> {
+        let n = unsafe { hdfsHSync(self.fs, self.f) };
 
         if n == -1 {
             return Err(Error::last_os_error());
         }
 
-        Ok(n as usize)
+        Ok(())
     }
 
-    fn flush(&mut self) -> Result<()> {
-        let n = unsafe { hdfsFlush(self.fs, self.f) };
+    /// Flushing a file opened for read is a no-op, so generic code that
+    /// flushes defensively won't fail on readers.
+    fn inner_flush(&self) -> Result<()> {
+        if !self.writable {
+            return Ok(());
+        }
+        self.take_sync_error()?;
+
+        let n = unsafe {
+            match self.flush_mode {
+                FlushMode::Buffer => hdfsFlush(self.fs, self.f),
+                FlushMode::Visible => hdfsHFlush(self.fs, self.f),
+                FlushMode::Durable => hdfsHSync(self.fs, self.f),
+            }
+        };
 
         if n == -1 {
             return Err(Error::last_os_error());
@@ -170,37 +529,528 @@ impl Write for File {
 
         Ok(())
     }
-}
 
-impl Read for &File {
-    fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
+    /// Check whether the filesystem handle is stale after an I/O error.
+    ///
+    /// hdfs reports `java.io.IOException: Filesystem closed` as a generic `EIO`,
+    /// so we probe the filesystem by stating `/`. If the probe also fails, the
+    /// handle is considered as closed (for example, by another `hdfsDisconnect`
+    /// on the same cached instance or a name node restart), and
+    /// [`ErrorKind::ConnectionReset`] will be returned instead.
+    fn check_stale(&self, err: Error) -> Error {
+        if err.raw_os_error() != Some(libc::EIO) {
+            return err;
+        }
+
+        match Client::new(self.fs).metadata("/") {
+            Ok(_) => err,
+            Err(probe) => Error::new(
+                ErrorKind::ConnectionReset,
+                format!("hdfs filesystem is stale, reconnect is required: {err}, probe: {probe}"),
+            ),
+        }
+    }
+
+    /// Wrap the file into a [`BufReader`] with a 1 MiB buffer.
+    ///
+    /// Every [`Read::read`] on `File` is a JNI call to `hdfsRead`, so byte
+    /// granular APIs like [`Read::bytes`] or [`BufRead::lines`](std::io::BufRead::lines) are extremely
+    /// slow on a raw `File`. Please use them on the buffered reader instead.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use std::io::BufRead;
+    ///
+    /// use hdrs::{Client, ClientBuilder};
+    ///
+    /// let fs = ClientBuilder::new("default")
+    ///     .with_user("default")
+    ///     .connect()
+    ///     .expect("client connect succeed");
+    /// let f = fs
+    ///     .open_file()
+    ///     .read(true)
+    ///     .open("/tmp/hello.txt")
+    ///     .expect("must open success");
+    ///
+    /// for line in f.buffered().lines() {
+    ///     let _ = line.expect("must read success");
+    /// }
+    /// ```
+    pub fn buffered(self) -> BufReader<File> {
+        BufReader::with_capacity(BUFFERED_CAPACITY, self)
+    }
+
+    /// Seek to the start of file, works only for files opened for read.
+    ///
+    /// HDFS only supports sequential writes, so rewinding a file opened for
+    /// write returns [`Unsupported`][ErrorKind::Unsupported] unless nothing
+    /// has been written yet.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use std::io::Read;
+    ///
+    /// use hdrs::{Client, ClientBuilder};
+    ///
+    /// let fs = ClientBuilder::new("default")
+    ///     .with_user("default")
+    ///     .connect()
+    ///     .expect("client connect succeed");
+    /// let mut f = fs
+    ///     .open_file()
+    ///     .read(true)
+    ///     .open("/tmp/hello.txt")
+    ///     .expect("must open success");
+    ///
+    /// let mut buf = String::new();
+    /// f.read_to_string(&mut buf).expect("must read success");
+    /// f.rewind().expect("must rewind success");
+    /// f.read_to_string(&mut buf).expect("must read success");
+    /// ```
+    pub fn rewind(&mut self) -> Result<()> {
+        self.seek(SeekFrom::Start(0)).map(|_| ())
+    }
+
+    /// Read into `buf` and returns the bytes read with the new position.
+    ///
+    /// The position reflects the cursor after the read. It's tracked on the
+    /// client side, so no extra call into libhdfs is involved.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use hdrs::{Client, ClientBuilder};
+    ///
+    /// let fs = ClientBuilder::new("default")
+    ///     .with_user("default")
+    ///     .connect()
+    ///     .expect("client connect succeed");
+    /// let mut f = fs
+    ///     .open_file()
+    ///     .read(true)
+    ///     .open("/tmp/hello.txt")
+    ///     .expect("must open success");
+    ///
+    /// let mut buf = vec![0; 1024];
+    /// let (n, pos) = f.read_and_position(&mut buf).expect("must read success");
+    /// ```
+    pub fn read_and_position(&mut self, buf: &mut [u8]) -> Result<(usize, u64)> {
+        let n = self.read(buf)?;
+        let pos = self.position();
+
+        Ok((n, pos as u64))
+    }
+
+    /// Append `buf` to the end of file and returns the offset at which the
+    /// bytes were written.
+    ///
+    /// HDFS only supports sequential writes, so a file opened for write or
+    /// append is always positioned at its end and there is no need to seek.
+    /// Namenode grants the single-writer lease to the opener and serializes
+    /// appends, so the returned offset is stable within a single writer. There
+    /// is no coordination across writers: others fail to open the file instead.
+    ///
+    /// Returns [`PermissionDenied`][ErrorKind::PermissionDenied] if the file is
+    /// not opened for write.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use hdrs::{Client, ClientBuilder};
+    ///
+    /// let fs = ClientBuilder::new("default")
+    ///     .with_user("default")
+    ///     .connect()
+    ///     .expect("client connect succeed");
+    /// let mut f = fs
+    ///     .open_file()
+    ///     .append(true)
+    ///     .open("/tmp/hello.txt")
+    ///     .expect("must open success");
+    /// let offset = f.append_bytes(b"Hello, World!").expect("must append success");
+    /// ```
+    pub fn append_bytes(&mut self, buf: &[u8]) -> Result<u64> {
+        self.check_writable()?;
+
+        let offset = self.position();
+        self.write_all(buf)?;
+
+        Ok(offset as u64)
+    }
+
+    /// Copy all bytes from `src` into this file and returns the total bytes
+    /// written.
+    ///
+    /// This is a tuned [`io::copy`][std::io::copy]: the buffer is sized to the
+    /// block size of the file so that every `hdfsWrite` fills a whole block,
+    /// which matters a lot for write throughput. The file will be flushed
+    /// after `src` is exhausted.
+    ///
+    /// Returns [`PermissionDenied`][ErrorKind::PermissionDenied] if the file is
+    /// not opened for write.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use hdrs::{Client, ClientBuilder};
+    ///
+    /// let fs = ClientBuilder::new("default")
+    ///     .with_user("default")
+    ///     .connect()
+    ///     .expect("client connect succeed");
+    /// let mut f = fs
+    ///     .open_file()
+    ///     .write(true)
+    ///     .create(true)
+    ///     .open("/tmp/hello.txt")
+    ///     .expect("must open success");
+    ///
+    /// let mut src = std::fs::File::open("/tmp/local.txt").expect("must open success");
+    /// let n = f.write_from(&mut src).expect("must write success");
+    /// ```
+    pub fn write_from(&mut self, src: &mut impl Read) -> Result<u64> {
+        self.check_writable()?;
+
+        let block_size = self.metadata().map(|m| m.block_size()).unwrap_or(0);
+        let mut buf = vec![0; write_buffer_size(block_size)];
+        debug!("write to file {} with buffer size {}", self.path, buf.len());
+
+        let mut total = 0;
+        loop {
+            let n = match src.read(&mut buf) {
+                Ok(0) => break,
+                Ok(n) => n,
+                Err(e) if e.kind() == ErrorKind::Interrupted => continue,
+                Err(e) => return Err(e),
+            };
+            self.write_all(&buf[..n])?;
+            total += n as u64;
+        }
+        self.flush()?;
+
+        Ok(total)
+    }
+
+    /// Write all `slices` in order and flush the file, useful for writing a
+    /// batch of pre-serialized records.
+    ///
+    /// Small slices are coalesced into a buffer of the file's block size, so
+    /// that the batch is written with as few `hdfsWrite` calls as possible.
+    /// Slices larger than the buffer are written directly.
+    ///
+    /// On failure, the error wraps a [`WriteAllFromError`] which tells how
+    /// many bytes of this batch have been written to hdfs before the failure.
+    /// It keeps the kind of the failed call and can still be classified by
+    /// [`is_retryable`][crate::is_retryable].
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use hdrs::{Client, ClientBuilder};
+    ///
+    /// let fs = ClientBuilder::new("default")
+    ///     .with_user("default")
+    ///     .connect()
+    ///     .expect("client connect succeed");
+    /// let mut f = fs
+    ///     .open_file()
+    ///     .write(true)
+    ///     .create(true)
+    ///     .open("/tmp/events.log")
+    ///     .expect("must open success");
+    ///
+    /// let records = vec![b"event-1\n".to_vec(), b"event-2\n".to_vec()];
+    /// let slices: Vec<&[u8]> = records.iter().map(|v| v.as_slice()).collect();
+    /// f.write_all_from(&slices).expect("must write success");
+    /// ```
+    pub fn write_all_from(&mut self, slices: &[&[u8]]) -> Result<()> {
+        self.check_writable()?;
+
+        let total: usize = slices.iter().map(|v| v.len()).sum();
+        let block_size = self.metadata().map(|m| m.block_size()).unwrap_or(0);
+        let cap = write_buffer_size(block_size).min(total);
+
+        let start = self.position();
+        self.inner_write_all_from(slices, cap).map_err(|e| {
+            let written = (self.position() - start) as u64;
+            debug!(
+                "write to file {} failed after {} bytes written: {}",
+                self.path, written, e
+            );
+            Error::new(e.kind(), WriteAllFromError::new(written, e))
+        })
+    }
+
+    fn inner_write_all_from(&mut self, slices: &[&[u8]], cap: usize) -> Result<()> {
+        let mut buf = Vec::with_capacity(cap);
+        for slice in slices {
+            if buf.len() + slice.len() > cap {
+                self.write_all(&buf)?;
+                buf.clear();
+            }
+            if slice.len() >= cap {
+                self.write_all(slice)?;
+            } else {
+                buf.extend_from_slice(slice);
+            }
+        }
+        self.write_all(&buf)?;
+
+        self.flush()
+    }
+
+    /// Read at `offset` without changing the cursor of file.
+    ///
+    /// Interrupted errors will be retried at most `3` times, the same as
+    /// [`Read::read`]. Returns `0` on EOF.
+    pub fn read_at(&self, buf: &mut [u8], offset: u64) -> Result<usize> {
+        retry_interrupted(|| self.inner_read_at(buf, offset))
+    }
+
+    /// Seek to `offset` and read into `buf`, leaving the cursor after the
+    /// bytes read.
+    ///
+    /// Unlike [`File::read_at`] which never touches the cursor, this moves
+    /// the cursor like [`Seek::seek`] followed by [`Read::read`], so later
+    /// sequential reads continue from `offset + n`. It matches the semantics
+    /// of `seek_read` in `std::os::windows::fs::FileExt`.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use std::io::Read;
+    ///
+    /// use hdrs::{Client, ClientBuilder};
+    ///
+    /// let fs = ClientBuilder::new("default")
+    ///     .with_user("default")
+    ///     .connect()
+    ///     .expect("client connect succeed");
+    /// let mut f = fs
+    ///     .open_file()
+    ///     .read(true)
+    ///     .open("/tmp/hello.txt")
+    ///     .expect("must open success");
+    ///
+    /// let mut buf = [0; 5];
+    /// let n = f.seek_read(7, &mut buf).expect("must read success");
+    /// // Continue reading from `7 + n`.
+    /// let mut rest = Vec::new();
+    /// f.read_to_end(&mut rest).expect("must read success");
+    /// ```
+    pub fn seek_read(&mut self, offset: u64, buf: &mut [u8]) -> Result<usize> {
+        self.seek(SeekFrom::Start(offset))?;
+        self.read(buf)
+    }
+
+    fn inner_read_at(&self, buf: &mut [u8], offset: u64) -> Result<usize> {
+        self.check_readable()?;
+
         let n = unsafe {
-            hdfsRead(
+            hdfsPread(
                 self.fs,
                 self.f,
+                offset as i64,
                 buf.as_ptr() as *mut c_void,
-                buf.len().min(FILE_LIMIT) as i32,
+                len_limit(buf.len().min(self.max_read_chunk)),
             )
         };
 
         if n == -1 {
-            return Err(Error::last_os_error());
+            return Err(self.check_stale(Error::last_os_error()));
         }
 
         Ok(n as usize)
     }
+
+    /// Create a reader over `len` bytes starting from `offset`.
+    ///
+    /// The reader uses positional reads, so the cursor of file will not be
+    /// changed. It returns EOF after `len` bytes or the end of file.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use std::io::Read;
+    ///
+    /// use hdrs::{Client, ClientBuilder};
+    ///
+    /// let fs = ClientBuilder::new("default")
+    ///     .with_user("default")
+    ///     .connect()
+    ///     .expect("client connect succeed");
+    /// let f = fs
+    ///     .open_file()
+    ///     .read(true)
+    ///     .open("/tmp/hello.txt")
+    ///     .expect("must open success");
+    ///
+    /// let mut buf = Vec::new();
+    /// f.range_reader(7, 5)
+    ///     .read_to_end(&mut buf)
+    ///     .expect("must read success");
+    /// ```
+    pub fn range_reader(&self, offset: u64, len: u64) -> RangeReader<'_> {
+        RangeReader::new(self, offset, len)
+    }
+
+    /// Read multiple non-contiguous ranges via positional reads.
+    ///
+    /// Every `(offset, buf)` pair will be read until `buf` is full or EOF
+    /// is reached. Returns the bytes read for each range in order.
+    ///
+    /// The cursor of file will not be changed.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use hdrs::{Client, ClientBuilder};
+    ///
+    /// let fs = ClientBuilder::new("default")
+    ///     .with_user("default")
+    ///     .connect()
+    ///     .expect("client connect succeed");
+    /// let f = fs
+    ///     .open_file()
+    ///     .read(true)
+    ///     .open("/tmp/hello.txt")
+    ///     .expect("must open success");
+    ///
+    /// let (mut header, mut footer) = (vec![0; 4], vec![0; 8]);
+    /// let ns = f
+    ///     .read_vectored_at(&mut [(0, &mut header), (1024, &mut footer)])
+    ///     .expect("must read success");
+    /// ```
+    pub fn read_vectored_at(&self, reqs: &mut [(u64, &mut [u8])]) -> Result<Vec<usize>> {
+        let mut ns = Vec::with_capacity(reqs.len());
+
+        for (offset, buf) in reqs.iter_mut() {
+            ns.push(self.read_full_at(buf, *offset)?);
+        }
+
+        Ok(ns)
+    }
+
+    /// Read at `offset` until `buf` is full or EOF is reached without
+    /// changing the cursor of file. Returns the bytes read.
+    pub(crate) fn read_full_at(&self, buf: &mut [u8], offset: u64) -> Result<usize> {
+        let mut read = 0;
+        while read < buf.len() {
+            let n = self.read_at(&mut buf[read..], offset + read as u64)?;
+            if n == 0 {
+                break;
+            }
+            read += n;
+        }
+
+        Ok(read)
+    }
+
+    /// Read `len` bytes at `offset` as a string without changing the cursor
+    /// of file.
+    ///
+    /// Returns [`UnexpectedEof`][ErrorKind::UnexpectedEof] if EOF is reached
+    /// before `len` bytes are read, and [`InvalidData`][ErrorKind::InvalidData]
+    /// if the bytes are not valid UTF-8.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use hdrs::{Client, ClientBuilder};
+    ///
+    /// let fs = ClientBuilder::new("default")
+    ///     .with_user("default")
+    ///     .connect()
+    ///     .expect("client connect succeed");
+    /// let f = fs
+    ///     .open_file()
+    ///     .read(true)
+    ///     .open("/tmp/data.parquet")
+    ///     .expect("must open success");
+    ///
+    /// let magic = f.read_string_at(0, 4).expect("must read success");
+    /// assert_eq!(magic, "PAR1");
+    /// ```
+    pub fn read_string_at(&self, offset: u64, len: usize) -> Result<String> {
+        let mut buf = vec![0; len];
+        let n = self.read_full_at(&mut buf, offset)?;
+        if n < len {
+            return Err(Error::new(
+                ErrorKind::UnexpectedEof,
+                format!("expect {len} bytes at {offset}, but only {n} bytes read"),
+            ));
+        }
+
+        String::from_utf8(buf).map_err(|e| Error::new(ErrorKind::InvalidData, e))
+    }
+}
+
+impl Read for File {
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
+        retry_interrupted(|| self.inner_read(buf))
+    }
+
+    fn read_vectored(&mut self, bufs: &mut [IoSliceMut<'_>]) -> Result<usize> {
+        self.inner_read_vectored(bufs)
+    }
+}
+
+impl Seek for File {
+    fn seek(&mut self, pos: SeekFrom) -> Result<u64> {
+        match pos {
+            SeekFrom::Start(n) => {
+                let offset = seek_offset(0, i64::try_from(n).map_err(|_| invalid_seek())?)?;
+                self.inner_seek(offset as i64)?;
+                Ok(offset)
+            }
+            SeekFrom::Current(n) => {
+                let offset = seek_offset(self.position(), n)?;
+                self.inner_seek(offset as i64)?;
+                Ok(offset)
+            }
+            SeekFrom::End(n) => {
+                let meta = Client::new(self.fs).metadata(&self.path)?;
+                let offset = seek_offset(meta.len() as i64, n)?;
+                self.inner_seek(offset as i64)?;
+                Ok(offset)
+            }
+        }
+    }
+}
+
+impl Write for File {
+    fn write(&mut self, buf: &[u8]) -> Result<usize> {
+        self.inner_write(buf)
+    }
+
+    fn flush(&mut self) -> Result<()> {
+        self.inner_flush()
+    }
+}
+
+impl Read for &File {
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
+        retry_interrupted(|| self.inner_read(buf))
+    }
+
+    fn read_vectored(&mut self, bufs: &mut [IoSliceMut<'_>]) -> Result<usize> {
+        self.inner_read_vectored(bufs)
+    }
 }
 
 impl Seek for &File {
     fn seek(&mut self, pos: SeekFrom) -> Result<u64> {
         match pos {
             SeekFrom::Start(n) => {
-                self.inner_seek(n as i64)?;
-                Ok(n)
+                let offset = seek_offset(0, i64::try_from(n).map_err(|_| invalid_seek())?)?;
+                self.inner_seek(offset as i64)?;
+                Ok(offset)
             }
             SeekFrom::Current(n) => {
-                let current = self.tell()?;
-                let offset = (current + n) as u64;
+                let offset = seek_offset(self.position(), n)?;
                 self.inner_seek(offset as i64)?;
                 Ok(offset)
             }
@@ -214,30 +1064,11 @@ impl Seek for &File {
 
 impl Write for &File {
     fn write(&mut self, buf: &[u8]) -> Result<usize> {
-        let n = unsafe {
-            hdfsWrite(
-                self.fs,
-                self.f,
-                buf.as_ptr() as *const c_void,
-                buf.len().min(FILE_LIMIT) as i32,
-            )
-        };
-
-        if n == -1 {
-            return Err(Error::last_os_error());
-        }
-
-        Ok(n as usize)
+        self.inner_write(buf)
     }
 
     fn flush(&mut self) -> Result<()> {
-        let n = unsafe { hdfsFlush(self.fs, self.f) };
-
-        if n == -1 {
-            return Err(Error::last_os_error());
-        }
-
-        Ok(())
+        self.inner_flush()
     }
 }
 
@@ -246,6 +1077,73 @@ mod tests {
     use super::*;
     use crate::client::ClientBuilder;
 
+    #[test]
+    fn test_retry_interrupted() {
+        let mut calls = 0;
+        let n = retry_interrupted(|| {
+            calls += 1;
+            if calls < 3 {
+                Err(Error::from_raw_os_error(libc::EINTR))
+            } else {
+                Ok(0)
+            }
+        });
+        assert_eq!(n.unwrap(), 0);
+        assert_eq!(calls, 3);
+
+        let mut calls = 0;
+        let n = retry_interrupted(|| {
+            calls += 1;
+            Err(Error::from_raw_os_error(libc::EINTR))
+        });
+        assert_eq!(n.unwrap_err().kind(), ErrorKind::Interrupted);
+        assert_eq!(calls, READ_RETRY_TIMES + 1);
+
+        let mut calls = 0;
+        let n = retry_interrupted(|| {
+            calls += 1;
+            Err(Error::from_raw_os_error(libc::EIO))
+        });
+        assert!(n.is_err());
+        assert_eq!(calls, 1);
+    }
+
+    #[test]
+    fn test_seek_offset() {
+        assert_eq!(seek_offset(0, 0).unwrap(), 0);
+        assert_eq!(seek_offset(10, -10).unwrap(), 0);
+        assert_eq!(seek_offset(10, 5).unwrap(), 15);
+        assert_eq!(
+            seek_offset(10, -11).unwrap_err().kind(),
+            ErrorKind::InvalidInput
+        );
+        assert_eq!(
+            seek_offset(0, i64::MIN).unwrap_err().kind(),
+            ErrorKind::InvalidInput
+        );
+        assert_eq!(
+            seek_offset(i64::MAX, 1).unwrap_err().kind(),
+            ErrorKind::InvalidInput
+        );
+    }
+
+    #[test]
+    fn test_write_buffer_size() {
+        assert_eq!(write_buffer_size(0), BUFFERED_CAPACITY);
+        assert_eq!(write_buffer_size(-1), BUFFERED_CAPACITY);
+        assert_eq!(write_buffer_size(128 * 1024 * 1024), 128 * 1024 * 1024);
+        assert_eq!(write_buffer_size(i64::MAX), FILE_LIMIT);
+    }
+
+    #[test]
+    fn test_len_limit() {
+        assert_eq!(len_limit(0), 0);
+        assert_eq!(len_limit(13), 13);
+        assert_eq!(len_limit(FILE_LIMIT), FILE_LIMIT as i32);
+        assert_eq!(len_limit(i32::MAX as usize + 1), FILE_LIMIT as i32);
+        assert_eq!(len_limit(usize::MAX), FILE_LIMIT as i32);
+    }
+
     #[test]
     fn test_file_build() {
         let _ = env_logger::try_init();
@@ -287,7 +1185,8 @@ mod tests {
         let n = f
             .write("Hello, World!".as_bytes())
             .expect("write must success");
-        assert_eq!(n, 13)
+        assert_eq!(n, 13);
+        assert_eq!(f.position(), f.tell().expect("tell must success"));
     }
 
     #[test]
@@ -0,0 +1,111 @@
+use std::io::{Read, Result, Seek, Write};
+
+use crate::{Client, File, Metadata, Readdir};
+
+/// Filesystem abstracts the operations provided by [`Client`].
+///
+/// Code that builds on hdrs can depend on this trait instead of [`Client`]
+/// so that it can be tested against another implementation like
+/// [`MockFilesystem`][crate::MockFilesystem] (behind the `mock` feature).
+///
+/// # Examples
+///
+/// ```no_run
+/// use std::io::{Read, Result};
+///
+/// use hdrs::{ClientBuilder, Filesystem};
+///
+/// fn read_all(fs: &impl Filesystem, path: &str) -> Result<Vec<u8>> {
+///     let mut buf = Vec::new();
+///     fs.open_read(path)?.read_to_end(&mut buf)?;
+///     Ok(buf)
+/// }
+///
+/// let fs = ClientBuilder::new("default")
+///     .connect()
+///     .expect("client connect succeed");
+/// let content = read_all(&fs, "/tmp/hello.txt");
+/// ```
+pub trait Filesystem {
+    /// The file type returned while opening.
+    type File: Read + Write + Seek;
+
+    /// Open a file for reading.
+    fn open_read(&self, path: &str) -> Result<Self::File>;
+
+    /// Open a file for writing, the file will be created if not exist and
+    /// truncated if exists.
+    fn open_write(&self, path: &str) -> Result<Self::File>;
+
+    /// Open a file for appending.
+    fn open_append(&self, path: &str) -> Result<Self::File>;
+
+    /// Stat a path to get file info.
+    fn metadata(&self, path: &str) -> Result<Metadata>;
+
+    /// Read file entries from a dir.
+    fn read_dir(&self, path: &str) -> Result<Readdir>;
+
+    /// Delete a file.
+    fn remove_file(&self, path: &str) -> Result<()>;
+
+    /// Delete a dir.
+    fn remove_dir(&self, path: &str) -> Result<()>;
+
+    /// Delete a dir recursively.
+    fn remove_dir_all(&self, path: &str) -> Result<()>;
+
+    /// Rename a file.
+    fn rename_file(&self, old_path: &str, new_path: &str) -> Result<()>;
+
+    /// Create dir and all it's parent directories.
+    fn create_dir(&self, path: &str) -> Result<()>;
+}
+
+impl Filesystem for Client {
+    type File = File;
+
+    fn open_read(&self, path: &str) -> Result<File> {
+        self.open_file().read(true).open(path)
+    }
+
+    fn open_write(&self, path: &str) -> Result<File> {
+        self.open_file()
+            .write(true)
+            .create(true)
+            .truncate(true)
+            .open(path)
+    }
+
+    fn open_append(&self, path: &str) -> Result<File> {
+        self.open_file().append(true).open(path)
+    }
+
+    fn metadata(&self, path: &str) -> Result<Metadata> {
+        Client::metadata(self, path)
+    }
+
+    fn read_dir(&self, path: &str) -> Result<Readdir> {
+        Client::read_dir(self, path)
+    }
+
+    fn remove_file(&self, path: &str) -> Result<()> {
+        Client::remove_file(self, path)
+    }
+
+    fn remove_dir(&self, path: &str) -> Result<()> {
+        Client::remove_dir(self, path)
+    }
+
+    fn remove_dir_all(&self, path: &str) -> Result<()> {
+        Client::remove_dir_all(self, path)
+    }
+
+    fn rename_file(&self, old_path: &str, new_path: &str) -> Result<()> {
+        Client::rename_file(self, old_path, new_path)
+    }
+
+    fn create_dir(&self, path: &str) -> Result<()> {
+        Client::create_dir(self, path)
+    }
+}
@@ -0,0 +1,14 @@
+/// FsStats is the space usage of a filesystem, returned by
+/// [`statfs`][crate::Client::statfs].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct FsStats {
+    /// Raw capacity of the filesystem in bytes.
+    pub capacity: u64,
+    /// Raw bytes used by the filesystem, including all replicas.
+    pub used: u64,
+    /// Raw bytes remaining, calculated by `capacity - used`.
+    ///
+    /// Space used by non-hdfs data on datanodes is not excluded, so this could
+    /// be larger than the `Remaining` reported by `hdfs dfsadmin -report`.
+    pub remaining: u64,
+}
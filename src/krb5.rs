@@ -0,0 +1,133 @@
+use std::fs;
+use std::io::{Error, ErrorKind, Result};
+
+/// Check the kerberos ticket cache before handing it to libhdfs.
+///
+/// libhdfs only fails with a generic authentication error deep in JNI if
+/// the cache is missing, so we validate it up front. Returns the default
+/// principal found in the cache if it could be parsed.
+///
+/// Only file caches (`/path` or `FILE:/path`) are validated, other cache
+/// types like `KEYRING:` or `KCM:` are passed through as is.
+pub(crate) fn check_ticket_cache(path: &str) -> Result<Option<String>> {
+    let file_path = match path.split_once(':') {
+        None => path,
+        Some(("FILE", p)) => p,
+        Some(_) => return Ok(None),
+    };
+
+    let content = fs::read(file_path).map_err(|e| {
+        Error::new(
+            e.kind(),
+            format!("kerberos ticket cache {path} is not readable: {e}"),
+        )
+    })?;
+    if content.is_empty() {
+        return Err(Error::new(
+            ErrorKind::InvalidData,
+            format!("kerberos ticket cache {path} is empty"),
+        ));
+    }
+
+    Ok(parse_principal(&content))
+}
+
+/// Parse the default principal from a MIT ccache file.
+///
+/// Only version 3 and 4 are supported, which are the only ones written by
+/// modern kerberos implementations.
+///
+/// Reference: <https://web.mit.edu/kerberos/krb5-devel/doc/formats/ccache_file_format.html>
+fn parse_principal(content: &[u8]) -> Option<String> {
+    let mut r = Cursor(content);
+
+    if r.take(1)? != [0x05] {
+        return None;
+    }
+    match r.take(1)?[0] {
+        0x03 => {}
+        0x04 => {
+            let header_len = u16::from_be_bytes(r.take(2)?.try_into().ok()?);
+            r.take(header_len as usize)?;
+        }
+        _ => return None,
+    }
+
+    // Skip name type.
+    r.take(4)?;
+    let components = r.u32()?;
+    let realm = r.string()?;
+    let mut names = Vec::new();
+    for _ in 0..components {
+        names.push(r.string()?);
+    }
+
+    Some(format!("{}@{}", names.join("/"), realm))
+}
+
+struct Cursor<'a>(&'a [u8]);
+
+impl<'a> Cursor<'a> {
+    fn take(&mut self, n: usize) -> Option<&'a [u8]> {
+        if self.0.len() < n {
+            return None;
+        }
+        let (head, tail) = self.0.split_at(n);
+        self.0 = tail;
+        Some(head)
+    }
+
+    fn u32(&mut self) -> Option<u32> {
+        Some(u32::from_be_bytes(self.take(4)?.try_into().ok()?))
+    }
+
+    fn string(&mut self) -> Option<String> {
+        let n = self.u32()?;
+        String::from_utf8(self.take(n as usize)?.to_vec()).ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn counted(s: &str) -> Vec<u8> {
+        let mut v = (s.len() as u32).to_be_bytes().to_vec();
+        v.extend_from_slice(s.as_bytes());
+        v
+    }
+
+    #[test]
+    fn test_parse_principal() {
+        let mut content = vec![0x05, 0x04, 0x00, 0x0c];
+        content.extend_from_slice(&[0; 12]);
+        content.extend_from_slice(&1u32.to_be_bytes());
+        content.extend_from_slice(&2u32.to_be_bytes());
+        content.extend(counted("EXAMPLE.COM"));
+        content.extend(counted("hdfs"));
+        content.extend(counted("namenode"));
+
+        assert_eq!(
+            parse_principal(&content),
+            Some("hdfs/namenode@EXAMPLE.COM".to_string())
+        );
+
+        // Truncated cache.
+        assert_eq!(parse_principal(&content[..20]), None);
+        // Unsupported version.
+        assert_eq!(parse_principal(&[0x05, 0x01]), None);
+    }
+
+    #[test]
+    fn test_check_ticket_cache() {
+        let err = check_ticket_cache("/not/exist/krb5cc").expect_err("must fail");
+        assert_eq!(err.kind(), ErrorKind::NotFound);
+        let err = check_ticket_cache("FILE:/not/exist/krb5cc").expect_err("must fail");
+        assert_eq!(err.kind(), ErrorKind::NotFound);
+
+        assert_eq!(
+            check_ticket_cache("KEYRING:persistent:1000").expect("must succeed"),
+            None
+        );
+    }
+}
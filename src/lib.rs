@@ -29,6 +29,8 @@
 //! # Features
 //!
 //! - `async_file`: Enable async operation support
+//! - `hdfs_3_0`: Enable APIs that require libhdfs from hadoop 3.0 or later, like block size larger than `i32`
+//! - `mock`: Enable [`MockFilesystem`], an in-memory [`Filesystem`] for testing without a running cluster, libhdfs is still linked
 //! - `vendored`: Ignore lib loading logic, enforce to complie and staticly link libhdfs
 //!
 //! # Compiletime
@@ -67,19 +69,66 @@
 mod client;
 pub use client::{Client, ClientBuilder};
 
+mod config;
+
+mod krb5;
+
 mod file;
-pub use file::File;
+pub use file::{File, FlushMode};
+
+mod range_reader;
+pub use range_reader::RangeReader;
 
 #[cfg(feature = "async_file")]
 mod async_file;
 #[cfg(feature = "async_file")]
 pub use async_file::AsyncFile;
 
+#[cfg(feature = "async_file")]
+mod executor;
+#[cfg(feature = "async_file")]
+pub use executor::set_max_blocking_threads;
+
 mod open_options;
 pub use open_options::OpenOptions;
 
+mod rename_options;
+pub use rename_options::RenameOptions;
+
 mod metadata;
-pub use metadata::Metadata;
+pub use metadata::{FileKind, Metadata, MetadataBuilder, MetadataParts};
 
 mod readdir;
-pub use readdir::Readdir;
+pub use readdir::{EntryFilter, Readdir, SortKey};
+
+mod dir_diff;
+pub use dir_diff::DirDiff;
+
+mod fs_stats;
+pub use fs_stats::FsStats;
+
+mod datanode_info;
+pub use datanode_info::{DatanodeInfo, DatanodeState};
+
+mod access_mode;
+pub use access_mode::AccessMode;
+
+mod dir_entry;
+pub use dir_entry::DirEntry;
+
+mod build_info;
+pub use build_info::{build_info, BuildInfo, LinkMode};
+
+mod error;
+pub use error::{is_retryable, WriteAllFromError};
+
+mod utils;
+pub use utils::{format_size, normalize_path, SizeUnit};
+
+mod filesystem;
+pub use filesystem::Filesystem;
+
+#[cfg(feature = "mock")]
+mod mock;
+#[cfg(feature = "mock")]
+pub use mock::{MockFile, MockFilesystem};
@@ -1,13 +1,29 @@
-use std::ffi::CStr;
+use std::ffi::{c_char, CStr};
+use std::io;
 use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
 use hdfs_sys::*;
 
+use crate::utils::format_iso8601;
+use crate::{Client, File};
+
 /// Metadata of a path.
-#[derive(Debug, Clone, PartialEq, Eq)]
+///
+/// # Ordering
+///
+/// Metadata is ordered by path first, compared by unicode code points as
+/// Hadoop does, so it can be used in `BTreeSet` or sorted directly. Other
+/// fields only break ties between metadata of the same path to keep the
+/// ordering consistent with `Eq`.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
 pub struct Metadata {
     /// the name of the file, like `file:/path/to/file`
+    ///
+    /// Must be the first field since the derived `Ord` relies on it.
     path: String,
+    /// the byte offset of the final component in `path`, cached for
+    /// [`Metadata::file_name`]
+    name_start: usize,
     /// the size of the file in bytes
     size: i64,
     /// file or directory
@@ -29,6 +45,25 @@ pub struct Metadata {
 }
 
 impl Metadata {
+    /// Create a builder to construct metadata, useful for test fixtures.
+    pub fn builder() -> MetadataBuilder {
+        MetadataBuilder {
+            meta: Metadata {
+                path: String::new(),
+                name_start: 0,
+                size: 0,
+                kind: tObjectKind_kObjectKindFile,
+                permissions: 0,
+                replication: 0,
+                block_size: 0,
+                owner: String::new(),
+                group: String::new(),
+                last_mod: 0,
+                last_access: 0,
+            },
+        }
+    }
+
     /// the path of the file, like `/path/to/file`
     ///
     /// # Notes
@@ -47,15 +82,62 @@ impl Metadata {
         &self.path
     }
 
-    /// the size of the file in bytes
+    /// the final component of the path, like `file` for `/path/to/file`
+    ///
+    /// Trailing `/` will be ignored, and the root `/` returns an empty string.
+    pub fn file_name(&self) -> &str {
+        self.path[self.name_start..].trim_end_matches('/')
+    }
+
+    /// Consume the metadata and returns the owned path.
+    ///
+    /// Same as [`Metadata::path`] but avoids a clone.
+    pub fn into_path(self) -> String {
+        self.path
+    }
+
+    /// Open this file for reading via given client.
     ///
-    /// Metadata is not a collection, so we will not provide `is_empty`.
-    /// Keep the same style with `std::fs::File`
-    #[allow(clippy::len_without_is_empty)]
+    /// The path returned by HDFS is already absolute, so it's passed to
+    /// [`OpenOptions::open`][crate::OpenOptions::open] as is without any
+    /// normalization. Opening a directory returns an error.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use hdrs::{Client, ClientBuilder};
+    ///
+    /// let fs = ClientBuilder::new("default")
+    ///     .with_user("default")
+    ///     .connect()
+    ///     .expect("client connect succeed");
+    /// for meta in fs.read_dir("/tmp").expect("read dir succeed").into_inner() {
+    ///     if meta.is_file() {
+    ///         let _ = meta.open(&fs).expect("open file succeed");
+    ///     }
+    /// }
+    /// ```
+    pub fn open(&self, fs: &Client) -> io::Result<File> {
+        if self.is_dir() {
+            return Err(io::Error::from_raw_os_error(libc::EISDIR));
+        }
+
+        fs.open_file().read(true).open(&self.path)
+    }
+
+    /// the size of the file in bytes
     pub fn len(&self) -> u64 {
         self.size as u64
     }
 
+    /// the file is zero bytes
+    ///
+    /// Directories are always zero-length on HDFS, so this will always be
+    /// `true` for them. Combine with [`Metadata::is_file`] to detect empty files.
+    pub fn is_empty(&self) -> bool {
+        self.size == 0
+    }
+
     /// file or directory
     pub fn is_dir(&self) -> bool {
         self.kind == tObjectKind_kObjectKindDirectory
@@ -66,6 +148,11 @@ impl Metadata {
         self.kind == tObjectKind_kObjectKindFile
     }
 
+    /// the kind of the path, useful for exhaustive matching
+    pub fn kind(&self) -> FileKind {
+        FileKind::from(self.kind)
+    }
+
     /// the permissions associated with the file
     pub fn permissions(&self) -> i16 {
         self.permissions
@@ -76,6 +163,19 @@ impl Metadata {
         self.replication
     }
 
+    /// the approximate storage consumed by the file in bytes, `len * replication`
+    ///
+    /// If replication is `0`, the default `dfs.replication` value `3` is used.
+    ///
+    /// This is an approximation that ignores under-replication and erasure coding.
+    pub fn space_consumed(&self) -> u64 {
+        let replication = match self.replication {
+            0 => DEFAULT_REPLICATION,
+            v => v as u64,
+        };
+        self.len() * replication
+    }
+
     /// the block size for the file
     pub fn block_size(&self) -> i64 {
         self.block_size
@@ -91,6 +191,22 @@ impl Metadata {
         &self.group
     }
 
+    /// the owner of the file, `None` if the filesystem doesn't have owner
+    ///
+    /// Some filesystems like object stores accessed via `hadoop-aws` return
+    /// empty or null owner.
+    pub fn owner_opt(&self) -> Option<&str> {
+        Some(self.owner.as_str()).filter(|v| !v.is_empty())
+    }
+
+    /// the group associated with the file, `None` if the filesystem doesn't have group
+    ///
+    /// Some filesystems like object stores accessed via `hadoop-aws` return
+    /// empty or null group.
+    pub fn group_opt(&self) -> Option<&str> {
+        Some(self.group.as_str()).filter(|v| !v.is_empty())
+    }
+
     /// the last modification time for the file in seconds
     pub fn modified(&self) -> SystemTime {
         UNIX_EPOCH
@@ -98,65 +214,319 @@ impl Metadata {
             .expect("must be valid SystemTime")
     }
 
+    /// the last modification time in ISO-8601 UTC, like `2024-06-01T12:34:56Z`
+    ///
+    /// Unlike [`Metadata::modified`], this never panics on out of range times.
+    pub fn modified_iso8601(&self) -> String {
+        format_iso8601(self.last_mod)
+    }
+
     /// the last access time for the file in seconds
     pub fn accessed(&self) -> SystemTime {
         UNIX_EPOCH
             .checked_add(Duration::from_secs(self.last_access as u64))
             .expect("must be valid SystemTime")
     }
+
+    /// Convert into plain [`MetadataParts`], useful for custom wire formats.
+    ///
+    /// Timestamps out of the range of milliseconds in `i64` are saturated.
+    /// [`FileKind::Other`] is not kept since `MetadataParts` only tells
+    /// dirs apart, see [`Metadata::from_parts`].
+    pub fn to_parts(&self) -> MetadataParts {
+        MetadataParts {
+            path: self.path.clone(),
+            len: self.len(),
+            is_dir: self.is_dir(),
+            permissions: self.permissions,
+            replication: self.replication,
+            block_size: self.block_size,
+            owner: self.owner.clone(),
+            group: self.group.clone(),
+            modified_ms: self.last_mod.saturating_mul(1000),
+            accessed_ms: self.last_access.saturating_mul(1000),
+        }
+    }
+
+    /// Construct metadata from plain [`MetadataParts`].
+    ///
+    /// Timestamps are truncated to seconds, which is the precision of libhdfs.
+    /// Non-dir parts always become [`FileKind::File`], so the round trip of
+    /// metadata with [`FileKind::Other`] is lossy.
+    pub fn from_parts(parts: MetadataParts) -> Metadata {
+        Metadata {
+            name_start: name_start(&parts.path),
+            path: parts.path,
+            size: parts.len as i64,
+            kind: if parts.is_dir {
+                tObjectKind_kObjectKindDirectory
+            } else {
+                tObjectKind_kObjectKindFile
+            },
+            permissions: parts.permissions,
+            replication: parts.replication,
+            block_size: parts.block_size,
+            owner: parts.owner,
+            group: parts.group,
+            last_mod: parts.modified_ms.div_euclid(1000),
+            last_access: parts.accessed_ms.div_euclid(1000),
+        }
+    }
+}
+
+/// MetadataParts is the plain form of [`Metadata`] with all fields public,
+/// converted by [`Metadata::to_parts`] and [`Metadata::from_parts`].
+///
+/// It's useful for custom wire formats that don't want to depend on the
+/// opaque `Metadata`. Fields will only be changed along with
+/// [`MetadataParts::VERSION`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MetadataParts {
+    /// the path of the file, like `/path/to/file`
+    pub path: String,
+    /// the size of the file in bytes
+    pub len: u64,
+    /// whether the path is a directory
+    pub is_dir: bool,
+    /// the permissions associated with the file
+    pub permissions: i16,
+    /// the count of replicas
+    pub replication: i16,
+    /// the block size for the file
+    pub block_size: i64,
+    /// the owner of the file
+    pub owner: String,
+    /// the group associated with the file
+    pub group: String,
+    /// the last modification time in milliseconds since unix epoch
+    pub modified_ms: i64,
+    /// the last access time in milliseconds since unix epoch
+    pub accessed_ms: i64,
+}
+
+impl MetadataParts {
+    /// The version of fields layout, will be bumped while fields changed.
+    pub const VERSION: u32 = 1;
+}
+
+/// FileKind is the kind of a path, returned by [`Metadata::kind`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum FileKind {
+    /// A regular file.
+    File,
+    /// A directory.
+    Directory,
+    /// A symlink.
+    ///
+    /// libhdfs only reports files and directories for now, this is reserved
+    /// for future versions.
+    Symlink,
+    /// Kinds unknown to hdrs, with the raw `tObjectKind` value.
+    Other(u32),
+}
+
+impl From<u32> for FileKind {
+    fn from(kind: u32) -> Self {
+        if kind == tObjectKind_kObjectKindFile {
+            FileKind::File
+        } else if kind == tObjectKind_kObjectKindDirectory {
+            FileKind::Directory
+        } else {
+            FileKind::Other(kind)
+        }
+    }
+}
+
+/// The default value of `dfs.replication`.
+const DEFAULT_REPLICATION: u64 = 3;
+
+/// The builder of [`Metadata`], useful for constructing test fixtures.
+///
+/// All fields are zero or empty by default, which means a file of zero bytes.
+///
+/// # Examples
+///
+/// ```
+/// use std::time::{Duration, UNIX_EPOCH};
+///
+/// use hdrs::Metadata;
+///
+/// let meta = Metadata::builder()
+///     .with_path("/tmp/hello.txt")
+///     .with_len(13)
+///     .with_modified(UNIX_EPOCH + Duration::from_secs(1024))
+///     .build();
+/// assert!(meta.is_file());
+/// assert_eq!(meta.len(), 13);
+/// ```
+#[derive(Debug, Clone)]
+pub struct MetadataBuilder {
+    meta: Metadata,
+}
+
+impl MetadataBuilder {
+    /// Set the path of the file, like `/path/to/file`
+    pub fn with_path(mut self, path: &str) -> Self {
+        self.meta.path = path.to_string();
+        self.meta.name_start = name_start(path);
+        self
+    }
+
+    /// Set the size of the file in bytes
+    pub fn with_len(mut self, len: u64) -> Self {
+        self.meta.size = len as i64;
+        self
+    }
+
+    /// Set whether the path is a directory
+    pub fn with_dir(mut self, is_dir: bool) -> Self {
+        self.meta.kind = if is_dir {
+            tObjectKind_kObjectKindDirectory
+        } else {
+            tObjectKind_kObjectKindFile
+        };
+        self
+    }
+
+    /// Set the permissions associated with the file
+    pub fn with_permissions(mut self, permissions: i16) -> Self {
+        self.meta.permissions = permissions;
+        self
+    }
+
+    /// Set the count of replicas
+    pub fn with_replication(mut self, replication: i16) -> Self {
+        self.meta.replication = replication;
+        self
+    }
+
+    /// Set the block size for the file
+    pub fn with_block_size(mut self, block_size: i64) -> Self {
+        self.meta.block_size = block_size;
+        self
+    }
+
+    /// Set the owner of the file
+    pub fn with_owner(mut self, owner: &str) -> Self {
+        self.meta.owner = owner.to_string();
+        self
+    }
+
+    /// Set the group associated with the file
+    pub fn with_group(mut self, group: &str) -> Self {
+        self.meta.group = group.to_string();
+        self
+    }
+
+    /// Set the last modification time, rounded down to seconds
+    pub fn with_modified(mut self, modified: SystemTime) -> Self {
+        self.meta.last_mod = secs_since_epoch(modified);
+        self
+    }
+
+    /// Set the last access time, rounded down to seconds
+    pub fn with_accessed(mut self, accessed: SystemTime) -> Self {
+        self.meta.last_access = secs_since_epoch(accessed);
+        self
+    }
+
+    /// Build the metadata
+    pub fn build(self) -> Metadata {
+        self.meta
+    }
+}
+
+/// Returns the seconds since `UNIX_EPOCH`, rounded down and negative for
+/// times before it.
+fn secs_since_epoch(t: SystemTime) -> i64 {
+    match t.duration_since(UNIX_EPOCH) {
+        Ok(d) => i64::try_from(d.as_secs()).unwrap_or(i64::MAX),
+        Err(e) => {
+            let d = e.duration();
+            let secs = i64::try_from(d.as_secs()).unwrap_or(i64::MAX);
+            if d.subsec_nanos() == 0 {
+                -secs
+            } else {
+                -secs - 1
+            }
+        }
+    }
 }
 
 impl From<hdfsFileInfo> for Metadata {
     fn from(hfi: hdfsFileInfo) -> Self {
+        // HDFS allows names that are not valid utf-8, replace the invalid
+        // bytes instead of failing the whole listing.
+        let path = parse_path(&unsafe { CStr::from_ptr(hfi.mName) }.to_string_lossy());
+
         Self {
-            path: {
-                let p = unsafe {
-                    CStr::from_ptr(hfi.mName)
-                        .to_str()
-                        .expect("hdfs owner must be valid utf-8")
-                };
-
-                match p.find(':') {
-                    None => p.to_string(),
-                    Some(idx) => match &p[..idx] {
-                        // `file:/path/to/file` => `/path/to/file`
-                        "file" => p[idx + 1..].to_string(),
-                        // `hdfs://127.0.0.1:9000/path/to/file` => `/path/to/file`
-                        _ => {
-                            // length of `hdfs://`
-                            let scheme = idx + 2;
-                            // the first occur of `/` in `127.0.0.1:9000/path/to/file`
-                            let endpoint = &p[scheme + 1..]
-                                .find('/')
-                                .expect("hdfs must returns an absolute path");
-                            p[scheme + endpoint + 1..].to_string()
-                        }
-                    },
-                }
-            },
+            name_start: name_start(&path),
+            path,
             size: hfi.mSize,
             kind: hfi.mKind,
             permissions: hfi.mPermissions,
             replication: hfi.mReplication,
             block_size: hfi.mBlockSize,
-            owner: unsafe {
-                CStr::from_ptr(hfi.mOwner)
-                    .to_str()
-                    .expect("hdfs owner must be valid utf-8")
-                    .into()
-            },
-            group: unsafe {
-                CStr::from_ptr(hfi.mGroup)
-                    .to_str()
-                    .expect("hdfs owner must be valid utf-8")
-                    .into()
-            },
+            owner: unsafe { nullable_str(hfi.mOwner) },
+            group: unsafe { nullable_str(hfi.mGroup) },
             last_mod: hfi.mLastMod,
             last_access: hfi.mLastAccess,
         }
     }
 }
 
+/// Convert a nullable C string into String, null will be converted into empty string
+/// and invalid UTF-8 will be replaced with `U+FFFD`.
+///
+/// # Safety
+///
+/// `ptr` must be null or point to a valid C string.
+unsafe fn nullable_str(ptr: *const c_char) -> String {
+    if ptr.is_null() {
+        return String::new();
+    }
+
+    CStr::from_ptr(ptr).to_string_lossy().into_owned()
+}
+
+/// Returns the final component of `path`, which could be a full URI like
+/// `hdfs://127.0.0.1:9000/path/to/file`.
+pub(crate) fn file_name(path: &str) -> &str {
+    path[name_start(path)..].trim_end_matches('/')
+}
+
+/// Returns the byte offset of the final component in `path`, trailing `/`
+/// will be ignored.
+fn name_start(path: &str) -> usize {
+    path.trim_end_matches('/')
+        .rfind('/')
+        .map_or(0, |idx| idx + 1)
+}
+
+/// Strip the scheme and endpoint from the name returned by hdfs.
+///
+/// - `file:/path/to/file` => `/path/to/file`
+/// - `hdfs://127.0.0.1:9000/path/to/file` => `/path/to/file`
+pub(crate) fn parse_path(p: &str) -> String {
+    match p.find(':') {
+        None => p.to_string(),
+        Some(idx) => match &p[..idx] {
+            // `file:/path/to/file` => `/path/to/file`
+            "file" => p[idx + 1..].to_string(),
+            // `hdfs://127.0.0.1:9000/path/to/file` => `/path/to/file`
+            _ => {
+                // length of `hdfs://`
+                let scheme = idx + 2;
+                // the first occur of `/` in `127.0.0.1:9000/path/to/file`
+                let endpoint = &p[scheme + 1..]
+                    .find('/')
+                    .expect("hdfs must returns an absolute path");
+                p[scheme + endpoint + 1..].to_string()
+            }
+        },
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use std::ffi::CString;
@@ -181,6 +551,7 @@ mod tests {
                 },
                 Metadata {
                     path: "/path/to/file".into(),
+                    name_start: name_start("/path/to/file"),
                     size: 123,
                     kind: 0,
                     permissions: 0,
@@ -207,6 +578,7 @@ mod tests {
                 },
                 Metadata {
                     path: "/path/to/file".into(),
+                    name_start: name_start("/path/to/file"),
                     size: 0,
                     kind: 0,
                     permissions: 0,
@@ -233,6 +605,7 @@ mod tests {
                 },
                 Metadata {
                     path: "/path/to/file".into(),
+                    name_start: name_start("/path/to/file"),
                     size: 0,
                     kind: 0,
                     permissions: 0,
@@ -244,6 +617,33 @@ mod tests {
                     last_access: 0,
                 },
             ),
+            (
+                hdfsFileInfo {
+                    mKind: 0,
+                    mName: CString::new("s3a://bucket/path/to/file")?.into_raw(),
+                    mLastMod: 0,
+                    mSize: 0,
+                    mReplication: 0,
+                    mBlockSize: 0,
+                    mOwner: std::ptr::null_mut(),
+                    mGroup: CString::new("")?.into_raw(),
+                    mPermissions: 0,
+                    mLastAccess: 0,
+                },
+                Metadata {
+                    path: "/path/to/file".into(),
+                    name_start: name_start("/path/to/file"),
+                    size: 0,
+                    kind: 0,
+                    permissions: 0,
+                    replication: 0,
+                    block_size: 0,
+                    owner: "".into(),
+                    group: "".into(),
+                    last_mod: 0,
+                    last_access: 0,
+                },
+            ),
         ];
 
         for case in cases {
@@ -254,4 +654,201 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn test_from_non_utf8_name() -> anyhow::Result<()> {
+        let meta = Metadata::from(hdfsFileInfo {
+            mKind: tObjectKind_kObjectKindFile,
+            mName: CString::new(b"/path/to/\xff.txt".to_vec())?.into_raw(),
+            mLastMod: 0,
+            mSize: 0,
+            mReplication: 0,
+            mBlockSize: 0,
+            mOwner: std::ptr::null_mut(),
+            mGroup: std::ptr::null_mut(),
+            mPermissions: 0,
+            mLastAccess: 0,
+        });
+
+        assert_eq!(meta.path(), "/path/to/\u{fffd}.txt");
+        assert_eq!(meta.file_name(), "\u{fffd}.txt");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_from_non_utf8_owner() -> anyhow::Result<()> {
+        let name = CString::new("/path/to/file")?;
+        let owner = CString::new(b"xuan\xffwo".to_vec())?;
+        let meta = Metadata::from(file_info(&name, Some(&owner), None));
+
+        assert_eq!(meta.owner(), "xuan\u{fffd}wo");
+        assert_eq!(meta.group(), "");
+
+        Ok(())
+    }
+
+    /// Build a file info borrowing `name`, `owner` and `group`, `None` is
+    /// passed as null.
+    fn file_info(name: &CStr, owner: Option<&CStr>, group: Option<&CStr>) -> hdfsFileInfo {
+        let ptr = |s: Option<&CStr>| s.map_or(std::ptr::null_mut(), |s| s.as_ptr() as *mut _);
+
+        hdfsFileInfo {
+            mKind: tObjectKind_kObjectKindFile,
+            mName: name.as_ptr() as *mut _,
+            mLastMod: 0,
+            mSize: 0,
+            mReplication: 0,
+            mBlockSize: 0,
+            mOwner: ptr(owner),
+            mGroup: ptr(group),
+            mPermissions: 0,
+            mLastAccess: 0,
+        }
+    }
+
+    #[test]
+    fn test_owner_group_opt() -> anyhow::Result<()> {
+        let name = CString::new("/path/to/file")?;
+        let xuanwo = CString::new("xuanwo")?;
+        let empty = CString::new("")?;
+
+        let cases = vec![
+            (Some(xuanwo.as_c_str()), Some("xuanwo")),
+            (Some(empty.as_c_str()), None),
+            (None, None),
+        ];
+
+        for (value, expect) in cases {
+            let meta = Metadata::from(file_info(&name, value, None));
+            assert_eq!(meta.owner_opt(), expect, "owner {value:?}");
+            assert_eq!(meta.owner(), expect.unwrap_or_default());
+            assert_eq!(meta.group_opt(), None);
+
+            let meta = Metadata::from(file_info(&name, None, value));
+            assert_eq!(meta.group_opt(), expect, "group {value:?}");
+            assert_eq!(meta.group(), expect.unwrap_or_default());
+            assert_eq!(meta.owner_opt(), None);
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_space_consumed() {
+        let cases = vec![(100, 1, 100), (100, 3, 300), (100, 0, 300), (0, 3, 0)];
+
+        for (len, replication, expect) in cases {
+            let meta = Metadata::builder()
+                .with_len(len)
+                .with_replication(replication)
+                .build();
+            assert_eq!(meta.space_consumed(), expect);
+        }
+    }
+
+    #[test]
+    fn test_ord_by_path() {
+        let small = Metadata::builder().with_path("/a").with_len(100).build();
+        let large = Metadata::builder().with_path("/b").with_len(1).build();
+        assert!(small < large);
+
+        let mut entries = [large, Metadata::builder().with_path("/é").build(), small];
+        entries.sort();
+        let paths: Vec<_> = entries.iter().map(|m| m.path()).collect();
+        assert_eq!(paths, vec!["/a", "/b", "/é"]);
+    }
+
+    #[test]
+    fn test_file_name() {
+        let cases = vec![
+            ("hdfs://127.0.0.1:9000/tmp/file", "file"),
+            ("/tmp/dir/", "dir"),
+            ("file", "file"),
+            ("/", ""),
+        ];
+
+        for (path, expect) in cases {
+            let meta = Metadata::builder().with_path(path).build();
+            assert_eq!(meta.file_name(), expect, "{path}");
+        }
+    }
+
+    #[test]
+    fn test_modified_iso8601() {
+        let meta = Metadata::builder()
+            .with_modified(UNIX_EPOCH + Duration::from_secs(1717245296))
+            .build();
+        assert_eq!(meta.modified_iso8601(), "2024-06-01T12:34:56Z");
+    }
+
+    #[test]
+    fn test_secs_since_epoch() {
+        let cases = vec![
+            (UNIX_EPOCH, 0),
+            (UNIX_EPOCH + Duration::from_millis(1500), 1),
+            (UNIX_EPOCH - Duration::from_secs(1), -1),
+            (UNIX_EPOCH - Duration::from_millis(1500), -2),
+        ];
+        for (t, expect) in cases {
+            assert_eq!(secs_since_epoch(t), expect, "{t:?}");
+        }
+
+        let meta = Metadata::builder()
+            .with_modified(UNIX_EPOCH - Duration::from_secs(86400))
+            .build();
+        assert_eq!(meta.modified_iso8601(), "1969-12-31T00:00:00Z");
+    }
+
+    #[test]
+    fn test_parts() {
+        let meta = Metadata::builder()
+            .with_path("/tmp/dir")
+            .with_dir(true)
+            .with_permissions(0o755)
+            .with_owner("hdfs")
+            .with_modified(UNIX_EPOCH + Duration::from_secs(1024))
+            .build();
+
+        let parts = meta.to_parts();
+        assert!(parts.is_dir);
+        assert_eq!(parts.modified_ms, 1_024_000);
+        assert_eq!(Metadata::from_parts(parts), meta);
+
+        let parts = MetadataParts {
+            path: "/tmp/file".to_string(),
+            len: 13,
+            modified_ms: 1999,
+            ..Default::default()
+        };
+        let meta = Metadata::from_parts(parts);
+        assert!(meta.is_file());
+        assert_eq!(meta.len(), 13);
+        assert_eq!(meta.modified(), UNIX_EPOCH + Duration::from_secs(1));
+
+        let meta = Metadata {
+            last_mod: i64::MAX,
+            last_access: i64::MIN,
+            ..Metadata::builder().build()
+        };
+        let parts = meta.to_parts();
+        assert_eq!(parts.modified_ms, i64::MAX);
+        assert_eq!(parts.accessed_ms, i64::MIN);
+
+        let meta = Metadata {
+            kind: 0,
+            ..Metadata::builder().build()
+        };
+        assert_eq!(meta.kind(), FileKind::Other(0));
+        assert_eq!(Metadata::from_parts(meta.to_parts()).kind(), FileKind::File);
+    }
+
+    #[test]
+    fn test_kind() {
+        let meta = Metadata::builder().build();
+        assert_eq!(meta.kind(), FileKind::File);
+        let meta = Metadata::builder().with_dir(true).build();
+        assert_eq!(meta.kind(), FileKind::Directory);
+        assert_eq!(FileKind::from(0), FileKind::Other(0));
+    }
 }
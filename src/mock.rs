@@ -0,0 +1,437 @@
+use std::collections::BTreeMap;
+use std::io::{Error, ErrorKind, Read, Result, Seek, SeekFrom, Write};
+use std::sync::{Arc, Mutex};
+
+use crate::{Filesystem, Metadata, Readdir};
+
+/// MockFilesystem is an in-memory [`Filesystem`] for testing without a
+/// running hdfs cluster.
+///
+/// Only the file tree is modeled: owner, group, permissions and times are
+/// not tracked. Parent directories will be created while opening a file for
+/// writing, just like hdfs does.
+///
+/// # Notes
+///
+/// The `mock` feature is additive, hdrs still depends on hdfs-sys, so
+/// libhdfs and `libjvm` are still required at build and link time, see
+/// [Compiletime][crate#compiletime]. The mock only removes the need for a
+/// running cluster.
+///
+/// # Examples
+///
+/// ```
+/// use std::io::{Read, Write};
+///
+/// use hdrs::{Filesystem, MockFilesystem};
+///
+/// let fs = MockFilesystem::new();
+/// let mut f = fs.open_write("/tmp/hello.txt").unwrap();
+/// f.write_all(b"Hello, World!").unwrap();
+///
+/// let mut s = String::new();
+/// fs.open_read("/tmp/hello.txt")
+///     .unwrap()
+///     .read_to_string(&mut s)
+///     .unwrap();
+/// assert_eq!(s, "Hello, World!");
+/// ```
+#[derive(Debug, Default)]
+pub struct MockFilesystem {
+    nodes: Mutex<BTreeMap<String, Node>>,
+}
+
+#[derive(Debug, Clone)]
+enum Node {
+    Dir,
+    File(Arc<Mutex<Vec<u8>>>),
+}
+
+/// File returned by [`MockFilesystem`].
+#[derive(Debug)]
+pub struct MockFile {
+    content: Arc<Mutex<Vec<u8>>>,
+    pos: u64,
+    append: bool,
+}
+
+impl MockFilesystem {
+    /// Create an empty MockFilesystem which only contains `/`.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, BTreeMap<String, Node>> {
+        self.nodes
+            .lock()
+            .expect("mock filesystem lock must not be poisoned")
+    }
+}
+
+/// Normalize path into `/path/to/file` without trailing slash.
+fn normalize(path: &str) -> String {
+    let path = path.trim_end_matches('/');
+    if path.starts_with('/') {
+        path.to_string()
+    } else {
+        format!("/{path}")
+    }
+}
+
+/// Returns the prefix shared by all children of dir.
+fn child_prefix(dir: &str) -> String {
+    if dir == "/" {
+        dir.to_string()
+    } else {
+        format!("{dir}/")
+    }
+}
+
+fn get(nodes: &BTreeMap<String, Node>, path: &str) -> Option<Node> {
+    if path == "/" {
+        return Some(Node::Dir);
+    }
+    nodes.get(path).cloned()
+}
+
+fn is_child(prefix: &str, key: &str) -> bool {
+    key.len() > prefix.len() && key.starts_with(prefix) && !key[prefix.len()..].contains('/')
+}
+
+fn has_descendant(nodes: &BTreeMap<String, Node>, path: &str) -> bool {
+    let prefix = child_prefix(path);
+    nodes.keys().any(|k| k.starts_with(&prefix))
+}
+
+fn create_dir_all(nodes: &mut BTreeMap<String, Node>, path: &str) -> Result<()> {
+    let mut current = String::new();
+    for part in path.split('/').filter(|v| !v.is_empty()) {
+        current.push('/');
+        current.push_str(part);
+
+        match nodes.get(&current) {
+            Some(Node::Dir) => {}
+            Some(Node::File(_)) => return Err(Error::from_raw_os_error(libc::ENOTDIR)),
+            None => {
+                nodes.insert(current.clone(), Node::Dir);
+            }
+        }
+    }
+    Ok(())
+}
+
+fn parent(path: &str) -> &str {
+    match path.rfind('/') {
+        None | Some(0) => "/",
+        Some(idx) => &path[..idx],
+    }
+}
+
+fn to_metadata(path: &str, node: &Node) -> Metadata {
+    match node {
+        Node::Dir => Metadata::builder().with_path(path).with_dir(true).build(),
+        Node::File(content) => {
+            let size = content
+                .lock()
+                .expect("mock file lock must not be poisoned")
+                .len();
+            Metadata::builder()
+                .with_path(path)
+                .with_len(size as u64)
+                .build()
+        }
+    }
+}
+
+impl Filesystem for MockFilesystem {
+    type File = MockFile;
+
+    fn open_read(&self, path: &str) -> Result<MockFile> {
+        let path = normalize(path);
+        match get(&self.lock(), &path) {
+            None => Err(Error::from_raw_os_error(libc::ENOENT)),
+            Some(Node::Dir) => Err(Error::from_raw_os_error(libc::EISDIR)),
+            Some(Node::File(content)) => Ok(MockFile {
+                content,
+                pos: 0,
+                append: false,
+            }),
+        }
+    }
+
+    fn open_write(&self, path: &str) -> Result<MockFile> {
+        let path = normalize(path);
+        let mut nodes = self.lock();
+        if let Some(Node::Dir) = get(&nodes, &path) {
+            return Err(Error::from_raw_os_error(libc::EISDIR));
+        }
+
+        create_dir_all(&mut nodes, parent(&path))?;
+        let content = Arc::new(Mutex::new(Vec::new()));
+        nodes.insert(path, Node::File(content.clone()));
+        Ok(MockFile {
+            content,
+            pos: 0,
+            append: false,
+        })
+    }
+
+    fn open_append(&self, path: &str) -> Result<MockFile> {
+        let path = normalize(path);
+        match get(&self.lock(), &path) {
+            None => Err(Error::from_raw_os_error(libc::ENOENT)),
+            Some(Node::Dir) => Err(Error::from_raw_os_error(libc::EISDIR)),
+            Some(Node::File(content)) => Ok(MockFile {
+                content,
+                pos: 0,
+                append: true,
+            }),
+        }
+    }
+
+    fn metadata(&self, path: &str) -> Result<Metadata> {
+        let path = normalize(path);
+        match get(&self.lock(), &path) {
+            None => Err(Error::from_raw_os_error(libc::ENOENT)),
+            Some(node) => Ok(to_metadata(&path, &node)),
+        }
+    }
+
+    fn read_dir(&self, path: &str) -> Result<Readdir> {
+        let path = normalize(path);
+        let nodes = self.lock();
+        match get(&nodes, &path) {
+            None => Err(Error::from_raw_os_error(libc::ENOENT)),
+            Some(Node::File(_)) => Err(Error::from_raw_os_error(libc::ENOTDIR)),
+            Some(Node::Dir) => {
+                let prefix = child_prefix(&path);
+                Ok(nodes
+                    .iter()
+                    .filter(|(k, _)| is_child(&prefix, k))
+                    .map(|(k, v)| to_metadata(k, v))
+                    .collect::<Vec<_>>()
+                    .into())
+            }
+        }
+    }
+
+    fn remove_file(&self, path: &str) -> Result<()> {
+        let path = normalize(path);
+        let mut nodes = self.lock();
+        if !nodes.contains_key(&path) {
+            return Err(Error::from_raw_os_error(libc::ENOENT));
+        }
+        if has_descendant(&nodes, &path) {
+            return Err(Error::from_raw_os_error(libc::ENOTEMPTY));
+        }
+
+        nodes.remove(&path);
+        Ok(())
+    }
+
+    fn remove_dir(&self, path: &str) -> Result<()> {
+        self.remove_file(path)
+    }
+
+    fn remove_dir_all(&self, path: &str) -> Result<()> {
+        let path = normalize(path);
+        let mut nodes = self.lock();
+        if nodes.remove(&path).is_none() {
+            return Err(Error::from_raw_os_error(libc::ENOENT));
+        }
+
+        let prefix = child_prefix(&path);
+        nodes.retain(|k, _| !k.starts_with(&prefix));
+        Ok(())
+    }
+
+    fn rename_file(&self, old_path: &str, new_path: &str) -> Result<()> {
+        let (old_path, new_path) = (normalize(old_path), normalize(new_path));
+        let mut nodes = self.lock();
+        if !nodes.contains_key(&old_path) {
+            return Err(Error::from_raw_os_error(libc::ENOENT));
+        }
+        match (get(&nodes, &old_path), get(&nodes, &new_path)) {
+            (_, None) => {}
+            (Some(Node::File(_)), Some(Node::Dir)) => {
+                return Err(Error::from_raw_os_error(libc::EISDIR))
+            }
+            (Some(Node::Dir), Some(Node::File(_))) => {
+                return Err(Error::from_raw_os_error(libc::ENOTDIR))
+            }
+            (_, Some(_)) => return Err(Error::from_raw_os_error(libc::EEXIST)),
+        }
+        match get(&nodes, parent(&new_path)) {
+            None => return Err(Error::from_raw_os_error(libc::ENOENT)),
+            Some(Node::File(_)) => return Err(Error::from_raw_os_error(libc::ENOTDIR)),
+            Some(Node::Dir) => {}
+        }
+
+        let old_prefix = child_prefix(&old_path);
+        let moved: Vec<String> = nodes
+            .keys()
+            .filter(|k| **k == old_path || k.starts_with(&old_prefix))
+            .cloned()
+            .collect();
+        for k in moved {
+            let node = nodes.remove(&k).expect("key must exist");
+            nodes.insert(format!("{new_path}{}", &k[old_path.len()..]), node);
+        }
+        Ok(())
+    }
+
+    fn create_dir(&self, path: &str) -> Result<()> {
+        let path = normalize(path);
+        let mut nodes = self.lock();
+        if let Some(Node::File(_)) = get(&nodes, &path) {
+            return Err(Error::from_raw_os_error(libc::EEXIST));
+        }
+
+        create_dir_all(&mut nodes, &path)
+    }
+}
+
+impl Read for MockFile {
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
+        let content = self
+            .content
+            .lock()
+            .expect("mock file lock must not be poisoned");
+        let start = (self.pos as usize).min(content.len());
+        let n = buf.len().min(content.len() - start);
+        buf[..n].copy_from_slice(&content[start..start + n]);
+        self.pos += n as u64;
+        Ok(n)
+    }
+}
+
+impl Write for MockFile {
+    fn write(&mut self, buf: &[u8]) -> Result<usize> {
+        let mut content = self
+            .content
+            .lock()
+            .expect("mock file lock must not be poisoned");
+        if self.append {
+            self.pos = content.len() as u64;
+        }
+
+        let start = self.pos as usize;
+        let end = start + buf.len();
+        if content.len() < end {
+            content.resize(end, 0);
+        }
+        content[start..end].copy_from_slice(buf);
+        self.pos = end as u64;
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> Result<()> {
+        Ok(())
+    }
+}
+
+impl Seek for MockFile {
+    fn seek(&mut self, pos: SeekFrom) -> Result<u64> {
+        let len = self
+            .content
+            .lock()
+            .expect("mock file lock must not be poisoned")
+            .len() as i64;
+        let offset = match pos {
+            SeekFrom::Start(n) => n as i64,
+            SeekFrom::Current(n) => self.pos as i64 + n,
+            SeekFrom::End(n) => len + n,
+        };
+        if offset < 0 {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "invalid seek to a negative position",
+            ));
+        }
+
+        self.pos = offset as u64;
+        Ok(self.pos)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mock_write_read() -> anyhow::Result<()> {
+        let fs = MockFilesystem::new();
+
+        let mut f = fs.open_write("/a/b/hello.txt")?;
+        f.write_all(b"Hello, World!")?;
+
+        assert!(fs.metadata("/a/b")?.is_dir());
+        let meta = fs.metadata("/a/b/hello.txt")?;
+        assert!(meta.is_file());
+        assert_eq!(meta.len(), 13);
+
+        let mut f = fs.open_append("/a/b/hello.txt")?;
+        f.write_all(b"!")?;
+
+        let mut f = fs.open_read("/a/b/hello.txt")?;
+        f.seek(SeekFrom::Start(7))?;
+        let mut s = String::new();
+        f.read_to_string(&mut s)?;
+        assert_eq!(s, "World!!");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_mock_read_dir() -> anyhow::Result<()> {
+        let fs = MockFilesystem::new();
+
+        fs.create_dir("/a/b/c")?;
+        fs.open_write("/a/hello.txt")?;
+
+        let paths = fs.read_dir("/a")?.into_paths();
+        assert_eq!(paths, vec!["/a/b", "/a/hello.txt"]);
+        assert_eq!(fs.read_dir("/")?.into_paths(), vec!["/a"]);
+        assert_eq!(
+            fs.read_dir("/a/not-exist").unwrap_err().kind(),
+            ErrorKind::NotFound
+        );
+        assert_eq!(
+            fs.read_dir("/a/hello.txt").unwrap_err().kind(),
+            ErrorKind::NotADirectory
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_mock_rename_and_remove() -> anyhow::Result<()> {
+        let fs = MockFilesystem::new();
+
+        fs.open_write("/a/b/hello.txt")?;
+        fs.create_dir("/c")?;
+
+        assert!(fs.remove_dir("/a").is_err());
+        fs.rename_file("/a/b", "/c/b")?;
+        assert!(fs.metadata("/c/b/hello.txt")?.is_file());
+        assert_eq!(
+            fs.metadata("/a/b/hello.txt").unwrap_err().kind(),
+            ErrorKind::NotFound
+        );
+
+        fs.open_write("/c/hello.txt")?;
+        assert_eq!(
+            fs.rename_file("/c/hello.txt", "/a").unwrap_err().kind(),
+            ErrorKind::IsADirectory
+        );
+        assert_eq!(
+            fs.rename_file("/c/b", "/c/hello.txt").unwrap_err().kind(),
+            ErrorKind::NotADirectory
+        );
+
+        fs.remove_dir_all("/c")?;
+        assert_eq!(fs.read_dir("/")?.into_paths(), vec!["/a"]);
+
+        Ok(())
+    }
+}
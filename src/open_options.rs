@@ -4,7 +4,7 @@ use std::io::{Error, ErrorKind, Result};
 use hdfs_sys::*;
 use log::debug;
 
-use crate::File;
+use crate::{Client, File, FlushMode};
 
 /// Options and flags which can be used to configure how a file is opened.
 ///
@@ -57,6 +57,9 @@ pub struct OpenOptions {
     buffer_size: usize,
     replication: usize,
     blocksize: usize,
+    flush_mode: FlushMode,
+    sync_on_write: bool,
+    max_read_chunk: usize,
 }
 
 /// HDFS's client handle is thread safe.
@@ -77,6 +80,9 @@ impl OpenOptions {
             buffer_size: 0,
             replication: 0,
             blocksize: 0,
+            flush_mode: FlushMode::Buffer,
+            sync_on_write: false,
+            max_read_chunk: 0,
         }
     }
 
@@ -105,11 +111,107 @@ impl OpenOptions {
     /// Pass `0` if you want to use the default configured values.
     ///
     /// `0` by default.
+    ///
+    /// `hdfsOpenFile` only accepts block size that fits in `i32`. Larger block
+    /// size requires the `hdfs_3_0` feature, with which the file will be opened
+    /// by the stream builder API instead. Otherwise, [`OpenOptions::open`] will
+    /// return [`InvalidInput`][ErrorKind::InvalidInput].
     pub fn with_blocksize(&mut self, blocksize: usize) -> &mut Self {
         self.blocksize = blocksize;
         self
     }
 
+    /// Sets the max bytes of a single read from hdfs.
+    ///
+    /// Every `read` and `read_at` on the opened file will be clamped to it,
+    /// which bounds the transient buffers allocated by libhdfs for each read.
+    /// Useful for services running many concurrent streams. Callers like
+    /// `read_exact` or `read_to_end` will loop on short reads as usual.
+    ///
+    /// Pass `0` if you want to use the default value `1 GiB`.
+    ///
+    /// `0` by default.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use hdrs::{Client, ClientBuilder};
+    ///
+    /// let fs = ClientBuilder::new("default")
+    ///     .with_user("default")
+    ///     .connect()
+    ///     .expect("client connect succeed");
+    /// let file = fs
+    ///     .open_file()
+    ///     .read(true)
+    ///     .with_max_read_chunk(4 * 1024 * 1024)
+    ///     .open("/tmp/hello.txt");
+    /// ```
+    pub fn with_max_read_chunk(&mut self, max_read_chunk: usize) -> &mut Self {
+        self.max_read_chunk = max_read_chunk;
+        self
+    }
+
+    /// Sets what [`Write::flush`][std::io::Write::flush] does on the opened file.
+    ///
+    /// [`FlushMode::Buffer`] by default.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use hdrs::{Client, ClientBuilder, FlushMode};
+    ///
+    /// let fs = ClientBuilder::new("default")
+    ///     .with_user("default")
+    ///     .connect()
+    ///     .expect("client connect succeed");
+    /// let file = fs
+    ///     .open_file()
+    ///     .write(true)
+    ///     .create(true)
+    ///     .with_flush_mode(FlushMode::Durable)
+    ///     .open("wal.log");
+    /// ```
+    pub fn with_flush_mode(&mut self, flush_mode: FlushMode) -> &mut Self {
+        self.flush_mode = flush_mode;
+        self
+    }
+
+    /// Sets the option to sync every write to datanodes' disks, like `O_SYNC`.
+    ///
+    /// Every successful `write` will be followed by `hdfsHSync`. Since the
+    /// bytes have been written, a failed sync doesn't fail that `write`: the
+    /// error will be returned by the next `write`, `flush` or
+    /// [`File::sync_all`] instead.
+    ///
+    /// This is very expensive since every write waits for all datanodes in
+    /// the pipeline to persist data. Usually it's preferable to write in
+    /// buffered mode and call [`File::sync_all`] or flush with
+    /// [`FlushMode::Durable`] at checkpoints.
+    ///
+    /// `false` by default.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use hdrs::{Client, ClientBuilder};
+    ///
+    /// let fs = ClientBuilder::new("default")
+    ///     .with_user("default")
+    ///     .connect()
+    ///     .expect("client connect succeed");
+    /// let file = fs
+    ///     .open_file()
+    ///     .write(true)
+    ///     .create(true)
+    ///     .sync_on_write(true)
+    ///     .open("ledger.log");
+    /// ```
+    pub fn sync_on_write(&mut self, sync_on_write: bool) -> &mut Self {
+        self.sync_on_write = sync_on_write;
+        self
+    }
+
     /// Sets the option for read access.
     ///
     /// This option, when true, will indicate that the file should be
@@ -339,6 +441,7 @@ impl OpenOptions {
     ///   directory components of the specified path.
     /// * [`AlreadyExists`]: `create_new` was specified and the file already
     ///   exists.
+    /// * [`IsADirectory`]: The specified path is a directory.
     /// * [`InvalidInput`]: Invalid combinations of open options (truncate
     ///   without write access, no access mode set, incompatible integer values,
     ///   etc.).
@@ -366,48 +469,161 @@ impl OpenOptions {
     ///
     /// [`AlreadyExists`]: io::ErrorKind::AlreadyExists
     /// [`InvalidInput`]: io::ErrorKind::InvalidInput
+    /// [`IsADirectory`]: io::ErrorKind::IsADirectory
     /// [`NotFound`]: io::ErrorKind::NotFound
     /// [`PermissionDenied`]: io::ErrorKind::PermissionDenied
     pub fn open(&self, path: &str) -> Result<File> {
         let flags = libc::O_CLOEXEC | self.get_access_mode()? | self.get_creation_mode()?;
 
+        let buffer_size: c_int = self.buffer_size.try_into().map_err(|_| {
+            Error::new(
+                ErrorKind::InvalidInput,
+                format!("`buffer_size` {} exceeds valid `c_int`", self.buffer_size),
+            )
+        })?;
+        let replication: c_short = self.replication.try_into().map_err(|_| {
+            Error::new(
+                ErrorKind::InvalidInput,
+                format!("`replication` {} exceeds valid `c_short`", self.replication),
+            )
+        })?;
+        let p = CString::new(path)?;
+
         debug!("open file {} with flags {}", path, flags);
-        let b = unsafe {
-            let p = CString::new(path)?;
-            let buffer_size: c_int = self.buffer_size.try_into().map_err(|_| {
-                Error::new(
-                    ErrorKind::InvalidInput,
-                    format!("`buffer_size` {} exceeds valid `c_int`", self.buffer_size),
-                )
-            })?;
-            let replication: c_short = self.replication.try_into().map_err(|_| {
-                Error::new(
-                    ErrorKind::InvalidInput,
-                    format!("`replication` {} exceeds valid `c_short`", self.replication),
+        let b = match i32::try_from(self.blocksize) {
+            Ok(blocksize) => unsafe {
+                hdfsOpenFile(
+                    self.fs,
+                    p.as_ptr(),
+                    flags,
+                    buffer_size,
+                    replication,
+                    blocksize,
                 )
-            })?;
-            let blocksize: i32 = self.blocksize.try_into().map_err(|_| {
-                Error::new(
+            },
+            #[cfg(feature = "hdfs_3_0")]
+            Err(_) => self.open_by_stream_builder(&p, flags, buffer_size, replication)?,
+            #[cfg(not(feature = "hdfs_3_0"))]
+            Err(_) => {
+                return Err(Error::new(
                     ErrorKind::InvalidInput,
-                    format!("`blocksize` {} exceeds valid `i32`", self.blocksize),
-                )
-            })?;
-            hdfsOpenFile(
-                self.fs,
-                p.as_ptr(),
-                flags,
-                buffer_size,
-                replication,
-                blocksize,
-            )
+                    format!(
+                        "`blocksize` {} exceeds valid `i32`, enable `hdfs_3_0` feature to use larger block size",
+                        self.blocksize
+                    ),
+                ))
+            }
         };
 
         if b.is_null() {
-            return Err(Error::last_os_error());
+            let err = Error::last_os_error();
+            // hdfs reports opening a dir as a generic error like `ENOENT`,
+            // stat the path to tell the user what is actually wrong.
+            if let Ok(true) = Client::new(self.fs).metadata(path).map(|m| m.is_dir()) {
+                return Err(Error::new(
+                    ErrorKind::IsADirectory,
+                    format!("cannot open dir {path} as a file"),
+                ));
+            }
+            return Err(err);
         }
 
         debug!("file {} with flags {} opened", path, flags);
-        Ok(File::new(self.fs, b, path))
+        Ok(File::new(
+            self.fs,
+            b,
+            path,
+            self.flush_mode,
+            self.sync_on_write,
+            self.max_read_chunk,
+        ))
+    }
+
+    /// Open file by the stream builder API which accepts `i64` block size.
+    #[cfg(feature = "hdfs_3_0")]
+    fn open_by_stream_builder(
+        &self,
+        path: &CString,
+        flags: c_int,
+        buffer_size: c_int,
+        replication: c_short,
+    ) -> Result<hdfsFile> {
+        let blocksize: i64 = self.blocksize.try_into().map_err(|_| {
+            Error::new(
+                ErrorKind::InvalidInput,
+                format!("`blocksize` {} exceeds valid `i64`", self.blocksize),
+            )
+        })?;
+
+        let bld = unsafe { hdfsStreamBuilderAlloc(self.fs, path.as_ptr(), flags) };
+        if bld.is_null() {
+            return Err(Error::last_os_error());
+        }
+
+        // Setters return errno directly, `0` means unchanged default.
+        let mut n = 0;
+        if buffer_size != 0 {
+            n = unsafe { hdfsStreamBuilderSetBufferSize(bld, buffer_size) };
+        }
+        if n == 0 && replication != 0 {
+            n = unsafe { hdfsStreamBuilderSetReplication(bld, replication) };
+        }
+        if n == 0 {
+            n = unsafe { hdfsStreamBuilderSetDefaultBlockSize(bld, blocksize) };
+        }
+        if n != 0 {
+            unsafe { hdfsStreamBuilderFree(bld) };
+            return Err(Error::from_raw_os_error(n));
+        }
+
+        // The builder is freed by build no matter it succeeds or not.
+        Ok(unsafe { hdfsStreamBuilderBuild(bld) })
+    }
+
+    /// Reopens the path of an existing `file` for read, with a fresh cursor.
+    ///
+    /// The access mode is derived from `file` instead of `self`, only other
+    /// options like buffer size will be used.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Unsupported`][ErrorKind::Unsupported] if `file` is opened for write,
+    /// since hdfs only allows one writer for a file.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use hdrs::{Client, ClientBuilder};
+    ///
+    /// let fs = ClientBuilder::new("default")
+    ///     .with_user("default")
+    ///     .connect()
+    ///     .expect("client connect succeed");
+    /// let file = fs
+    ///     .open_file()
+    ///     .read(true)
+    ///     .open("foo.txt")
+    ///     .expect("must open success");
+    /// let another = fs.open_file().reopen(&file);
+    /// ```
+    pub fn reopen(&self, file: &File) -> Result<File> {
+        if file.is_open_for_write() {
+            return Err(Error::new(
+                ErrorKind::Unsupported,
+                "reopen a file opened for write is not supported, hdfs only allows one writer",
+            ));
+        }
+
+        let opt = OpenOptions {
+            read: true,
+            write: false,
+            append: false,
+            truncate: false,
+            create: false,
+            create_new: false,
+            ..self.clone()
+        };
+        opt.open(file.path())
     }
 
     #[cfg(feature = "async_file")]
@@ -0,0 +1,65 @@
+use std::io::{Read, Result};
+
+use crate::File;
+
+/// RangeReader reads a fixed range of a [`File`] via positional reads.
+///
+/// It's returned by [`File::range_reader`] which borrows the file, or by
+/// [`Client::open_at_length`][crate::Client::open_at_length] which owns it.
+/// The cursor of file will not be changed, so multiple range readers over one
+/// file can coexist.
+#[derive(Debug)]
+pub struct RangeReader<'a> {
+    file: Source<'a>,
+    offset: u64,
+    remaining: u64,
+}
+
+#[derive(Debug)]
+enum Source<'a> {
+    Borrowed(&'a File),
+    Owned(File),
+}
+
+impl<'a> RangeReader<'a> {
+    pub(crate) fn new(file: &'a File, offset: u64, len: u64) -> Self {
+        RangeReader {
+            file: Source::Borrowed(file),
+            offset,
+            remaining: len,
+        }
+    }
+
+    fn file(&self) -> &File {
+        match &self.file {
+            Source::Borrowed(f) => f,
+            Source::Owned(f) => f,
+        }
+    }
+}
+
+impl RangeReader<'static> {
+    pub(crate) fn owned(file: File, offset: u64, len: u64) -> Self {
+        RangeReader {
+            file: Source::Owned(file),
+            offset,
+            remaining: len,
+        }
+    }
+}
+
+impl Read for RangeReader<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
+        if self.remaining == 0 {
+            return Ok(0);
+        }
+
+        let size = buf
+            .len()
+            .min(self.remaining.try_into().unwrap_or(usize::MAX));
+        let n = self.file().read_at(&mut buf[..size], self.offset)?;
+        self.offset += n as u64;
+        self.remaining -= n as u64;
+        Ok(n)
+    }
+}
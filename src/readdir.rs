@@ -1,10 +1,105 @@
 use std::ops::{Deref, DerefMut};
 use std::vec::IntoIter;
 
-use crate::Metadata;
+use crate::{FileKind, Metadata};
+
+/// SortKey decides the order of entries returned by
+/// [`read_dir_sorted_by`][crate::Client::read_dir_sorted_by].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SortKey {
+    /// Sort by path in ascending order.
+    Name,
+    /// Sort by modification time, oldest first.
+    ModifiedAsc,
+    /// Sort by modification time, newest first.
+    ModifiedDesc,
+    /// Sort by size, smallest first.
+    SizeAsc,
+    /// Sort by size, largest first.
+    SizeDesc,
+}
+
+impl SortKey {
+    /// Sort entries in place by this key.
+    pub(crate) fn sort(self, entries: &mut [Metadata]) {
+        match self {
+            SortKey::Name => entries.sort_by(|a, b| a.path().cmp(b.path())),
+            SortKey::ModifiedAsc => entries.sort_by_key(|m| m.modified()),
+            SortKey::ModifiedDesc => entries.sort_by_key(|m| std::cmp::Reverse(m.modified())),
+            SortKey::SizeAsc => entries.sort_by_key(|m| m.len()),
+            SortKey::SizeDesc => entries.sort_by_key(|m| std::cmp::Reverse(m.len())),
+        }
+    }
+}
+
+/// EntryFilter decides which entries are returned by
+/// [`read_dir_with`][crate::Client::read_dir_with].
+///
+/// Prefix and suffix are matched against the file name, like `file.parquet`
+/// for `/path/to/file.parquet`. Entries must match all constraints, and an
+/// empty filter matches everything.
+///
+/// # Examples
+///
+/// ```
+/// use hdrs::{EntryFilter, FileKind};
+///
+/// let filter = EntryFilter::new()
+///     .with_suffix(".parquet")
+///     .with_kind(FileKind::File);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct EntryFilter {
+    prefix: Option<String>,
+    suffix: Option<String>,
+    kind: Option<FileKind>,
+}
+
+impl EntryFilter {
+    /// Create a filter that matches everything.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Only match entries whose file name starts with `prefix`.
+    pub fn with_prefix(mut self, prefix: &str) -> Self {
+        self.prefix = Some(prefix.to_string());
+        self
+    }
+
+    /// Only match entries whose file name ends with `suffix`.
+    pub fn with_suffix(mut self, suffix: &str) -> Self {
+        self.suffix = Some(suffix.to_string());
+        self
+    }
+
+    /// Only match entries of `kind`.
+    pub fn with_kind(mut self, kind: FileKind) -> Self {
+        self.kind = Some(kind);
+        self
+    }
+
+    /// Returns `true` if an entry with `name` and `kind` matches the filter.
+    pub(crate) fn matches(&self, name: &str, kind: FileKind) -> bool {
+        if let Some(prefix) = &self.prefix {
+            if !name.starts_with(prefix.as_str()) {
+                return false;
+            }
+        }
+        if let Some(suffix) = &self.suffix {
+            if !name.ends_with(suffix.as_str()) {
+                return false;
+            }
+        }
+        self.kind.is_none() || self.kind == Some(kind)
+    }
+}
 
 /// Readdir is an Iterator of metadata, returned by [`read_dir`][crate::Client::read_dir]
-#[derive(Debug)]
+///
+/// Entries are listed eagerly, so Readdir can be cloned to iterate the
+/// remaining entries again without another listing RPC.
+#[derive(Debug, Clone)]
 pub struct Readdir {
     inner: IntoIter<Metadata>,
 }
@@ -13,6 +108,31 @@ impl Readdir {
     pub fn into_inner(self) -> IntoIter<Metadata> {
         self.inner
     }
+
+    /// Returns the number of remaining entries.
+    pub fn len(&self) -> usize {
+        self.inner.len()
+    }
+
+    /// Returns `true` if there is no remaining entry.
+    pub fn is_empty(&self) -> bool {
+        self.inner.len() == 0
+    }
+
+    /// Returns a reference to the next entry without advancing.
+    pub fn peek(&self) -> Option<&Metadata> {
+        self.inner.as_slice().first()
+    }
+
+    /// Collect the remaining entries without consuming.
+    pub fn to_vec(&self) -> Vec<Metadata> {
+        self.inner.as_slice().to_vec()
+    }
+
+    /// Consume the Readdir and collect the path of every remaining entry.
+    pub fn into_paths(self) -> Vec<String> {
+        self.inner.map(Metadata::into_path).collect()
+    }
 }
 
 impl From<Vec<Metadata>> for Readdir {
@@ -36,3 +156,79 @@ impl DerefMut for Readdir {
         &mut self.inner
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_entry_filter() {
+        let cases = vec![
+            (EntryFilter::new(), "a.parquet", FileKind::File, true),
+            (EntryFilter::new(), "dir", FileKind::Directory, true),
+            (
+                EntryFilter::new().with_suffix(".parquet"),
+                "a.parquet",
+                FileKind::File,
+                true,
+            ),
+            (
+                EntryFilter::new().with_suffix(".parquet"),
+                "a.csv",
+                FileKind::File,
+                false,
+            ),
+            (
+                EntryFilter::new()
+                    .with_prefix("part-")
+                    .with_suffix(".parquet"),
+                "part-0.parquet",
+                FileKind::File,
+                true,
+            ),
+            (
+                EntryFilter::new().with_prefix("part-"),
+                "_SUCCESS",
+                FileKind::File,
+                false,
+            ),
+            (
+                EntryFilter::new().with_kind(FileKind::File),
+                "dir",
+                FileKind::Directory,
+                false,
+            ),
+        ];
+
+        for (filter, name, kind, expect) in cases {
+            assert_eq!(filter.matches(name, kind), expect, "{filter:?} {name}");
+        }
+    }
+
+    #[test]
+    fn test_readdir_len_and_peek() {
+        let mut readdir = Readdir::from(vec![
+            Metadata::builder().with_path("/a").build(),
+            Metadata::builder().with_path("/b").build(),
+        ]);
+        assert_eq!(readdir.len(), 2);
+        assert_eq!(readdir.peek().map(|m| m.path()), Some("/a"));
+        // Peek doesn't advance.
+        assert_eq!(readdir.len(), 2);
+
+        assert_eq!(
+            readdir.inner.next().map(|m| m.into_path()),
+            Some("/a".to_string())
+        );
+        assert_eq!(readdir.peek().map(|m| m.path()), Some("/b"));
+        assert_eq!(readdir.len(), 1);
+
+        let cloned = readdir.clone();
+        assert_eq!(readdir.to_vec(), cloned.into_inner().collect::<Vec<_>>());
+        assert_eq!(readdir.len(), 1);
+
+        readdir.inner.next();
+        assert!(readdir.is_empty());
+        assert_eq!(readdir.peek(), None);
+    }
+}
@@ -0,0 +1,95 @@
+use std::io::Result;
+
+use hdfs_sys::*;
+
+use crate::Client;
+
+/// Options which can be used to configure how a file is renamed.
+///
+/// This builder is returned by [`Client::rename`], and executed by [`RenameOptions::run`].
+///
+/// # Symlinks
+///
+/// Rename always operates on the link itself instead of its target, the same
+/// as POSIX `mv`. libhdfs doesn't expose symlink resolution, so there is no
+/// option to follow links.
+///
+/// # Examples
+///
+/// Rename a file and overwrite the destination if it exists:
+///
+/// ```no_run
+/// use hdrs::{Client, ClientBuilder};
+///
+/// let fs = ClientBuilder::new("default")
+///     .with_user("default")
+///     .connect()
+///     .expect("client connect succeed");
+/// let result = fs
+///     .rename("/tmp/hello.txt._COPY_", "/tmp/output/hello.txt")
+///     .overwrite(true)
+///     .create_parents(true)
+///     .run();
+/// ```
+#[derive(Debug, Clone)]
+pub struct RenameOptions {
+    fs: hdfsFS,
+    old_path: String,
+    new_path: String,
+
+    overwrite: bool,
+    create_parents: bool,
+}
+
+/// HDFS's client handle is thread safe.
+unsafe impl Send for RenameOptions {}
+unsafe impl Sync for RenameOptions {}
+
+impl RenameOptions {
+    pub(crate) fn new(fs: hdfsFS, old_path: &str, new_path: &str) -> Self {
+        RenameOptions {
+            fs,
+            old_path: old_path.to_string(),
+            new_path: new_path.to_string(),
+
+            overwrite: false,
+            create_parents: false,
+        }
+    }
+
+    /// Sets the option to overwrite the destination if it exists.
+    ///
+    /// See [`Client::rename_file_overwrite`] for how the destination is
+    /// replaced, the operation is not atomic.
+    ///
+    /// `false` by default.
+    pub fn overwrite(&mut self, overwrite: bool) -> &mut Self {
+        self.overwrite = overwrite;
+        self
+    }
+
+    /// Sets the option to create parent directories of the destination.
+    ///
+    /// `false` by default.
+    pub fn create_parents(&mut self, create_parents: bool) -> &mut Self {
+        self.create_parents = create_parents;
+        self
+    }
+
+    /// Renames the file with the options specified by `self`.
+    pub fn run(&self) -> Result<()> {
+        let client = Client::new(self.fs);
+
+        if self.create_parents {
+            client.create_dir_for_file(&self.new_path)?;
+        }
+
+        if self.overwrite {
+            client.rename_file_overwrite(&self.old_path, &self.new_path)?;
+        } else {
+            client.rename_file(&self.old_path, &self.new_path)?;
+        }
+
+        Ok(())
+    }
+}
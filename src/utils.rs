@@ -0,0 +1,176 @@
+/// Units used by [`format_size`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SizeUnit {
+    /// Powers of 1024: `KiB`, `MiB`, `GiB`, ...
+    Binary,
+    /// Powers of 1000: `KB`, `MB`, `GB`, ...
+    Decimal,
+}
+
+/// Format bytes into human-readable size like `1.5 GiB` or `512 MiB`.
+///
+/// Sizes are rounded to at most one decimal place, the same as `hdfs dfs -du -h`.
+///
+/// # Examples
+///
+/// ```
+/// use hdrs::{format_size, SizeUnit};
+///
+/// assert_eq!(format_size(512 * 1024 * 1024, SizeUnit::Binary), "512 MiB");
+/// assert_eq!(format_size(1_500_000_000, SizeUnit::Decimal), "1.5 GB");
+/// ```
+pub fn format_size(bytes: u64, unit: SizeUnit) -> String {
+    let (base, units) = match unit {
+        SizeUnit::Binary => (1024.0, ["B", "KiB", "MiB", "GiB", "TiB", "PiB", "EiB"]),
+        SizeUnit::Decimal => (1000.0, ["B", "KB", "MB", "GB", "TB", "PB", "EB"]),
+    };
+
+    let mut size = bytes as f64;
+    let mut idx = 0;
+    // Round before comparing so that we will not output `1024.0 KiB`.
+    while (size * 10.0).round() / 10.0 >= base && idx < units.len() - 1 {
+        size /= base;
+        idx += 1;
+    }
+
+    if idx == 0 {
+        return format!("{bytes} {}", units[0]);
+    }
+
+    let s = format!("{size:.1}");
+    let s = s.strip_suffix(".0").unwrap_or(&s);
+    format!("{s} {}", units[idx])
+}
+
+/// Normalize path so that different spellings of the same path are equal.
+///
+/// Rules:
+///
+/// - Duplicate slashes are collapsed: `/a//b` => `/a/b`.
+/// - Trailing slash is removed except for root: `/a/b/` => `/a/b`, `/` => `/`.
+/// - Scheme and authority are kept as is: `hdfs://host:9000//a/` => `hdfs://host:9000/a`.
+///
+/// `.` and `..` are not resolved since they are not valid path elements in
+/// hadoop, see [`Metadata::path`][crate::Metadata::path].
+///
+/// # Examples
+///
+/// ```
+/// use hdrs::normalize_path;
+///
+/// assert_eq!(normalize_path("/tmp//hello/"), "/tmp/hello");
+/// ```
+pub fn normalize_path(path: &str) -> String {
+    // Keep `scheme://authority` untouched.
+    let (prefix, path) = match path.find("://") {
+        Some(idx) => {
+            let authority_end = path[idx + 3..]
+                .find('/')
+                .map(|v| idx + 3 + v)
+                .unwrap_or(path.len());
+            path.split_at(authority_end)
+        }
+        None => ("", path),
+    };
+
+    let mut s = String::with_capacity(prefix.len() + path.len());
+    s.push_str(prefix);
+    for c in path.chars() {
+        if c == '/' && s.len() > prefix.len() && s.ends_with('/') {
+            continue;
+        }
+        s.push(c);
+    }
+
+    if s.len() > prefix.len() + 1 && s.ends_with('/') {
+        s.pop();
+    }
+    s
+}
+
+/// Format seconds since unix epoch into ISO-8601 UTC like `2024-06-01T12:34:56Z`.
+///
+/// The civil date is computed by Howard Hinnant's `civil_from_days`, which
+/// works for times before unix epoch too.
+/// Reference: <https://howardhinnant.github.io/date_algorithms.html#civil_from_days>
+pub(crate) fn format_iso8601(secs: i64) -> String {
+    let (days, secs) = (secs.div_euclid(86400), secs.rem_euclid(86400));
+
+    let z = days + 719468;
+    let era = z.div_euclid(146097);
+    let doe = z.rem_euclid(146097);
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+
+    format!(
+        "{year:04}-{month:02}-{day:02}T{:02}:{:02}:{:02}Z",
+        secs / 3600,
+        secs % 3600 / 60,
+        secs % 60
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_path() {
+        let cases = vec![
+            ("", ""),
+            ("/", "/"),
+            ("//", "/"),
+            ("/a/b", "/a/b"),
+            ("/a/b/", "/a/b"),
+            ("/a//b///c//", "/a/b/c"),
+            ("a//b/", "a/b"),
+            ("hdfs://127.0.0.1:9000", "hdfs://127.0.0.1:9000"),
+            ("hdfs://127.0.0.1:9000/", "hdfs://127.0.0.1:9000/"),
+            ("hdfs://127.0.0.1:9000//a//b/", "hdfs://127.0.0.1:9000/a/b"),
+            ("file:///tmp//a/", "file:///tmp/a"),
+        ];
+
+        for (path, expect) in cases {
+            assert_eq!(normalize_path(path), expect, "normalize {path}");
+        }
+    }
+
+    #[test]
+    fn test_format_iso8601() {
+        let cases = vec![
+            (0, "1970-01-01T00:00:00Z"),
+            (1717245296, "2024-06-01T12:34:56Z"),
+            (951782400, "2000-02-29T00:00:00Z"),
+            (4107542399, "2100-02-28T23:59:59Z"),
+            (-1, "1969-12-31T23:59:59Z"),
+        ];
+
+        for (secs, expect) in cases {
+            assert_eq!(format_iso8601(secs), expect, "format {secs}");
+        }
+    }
+
+    #[test]
+    fn test_format_size() {
+        let cases = vec![
+            (0, SizeUnit::Binary, "0 B"),
+            (1023, SizeUnit::Binary, "1023 B"),
+            (1024, SizeUnit::Binary, "1 KiB"),
+            (1536, SizeUnit::Binary, "1.5 KiB"),
+            (1024 * 1024 - 1, SizeUnit::Binary, "1 MiB"),
+            (3 * 1024 * 1024 * 1024 / 2, SizeUnit::Binary, "1.5 GiB"),
+            (u64::MAX, SizeUnit::Binary, "16 EiB"),
+            (999, SizeUnit::Decimal, "999 B"),
+            (1000, SizeUnit::Decimal, "1 KB"),
+            (512_000_000, SizeUnit::Decimal, "512 MB"),
+        ];
+
+        for (bytes, unit, expect) in cases {
+            assert_eq!(format_size(bytes, unit), expect, "format {bytes}");
+        }
+    }
+}
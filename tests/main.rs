@@ -1,7 +1,8 @@
+use std::time::Duration;
 use std::{env, io};
 
 use anyhow::Result;
-use hdrs::ClientBuilder;
+use hdrs::{ClientBuilder, EntryFilter, FileKind, FlushMode};
 use log::debug;
 use rand::{Rng, RngCore};
 
@@ -17,6 +18,76 @@ fn test_connect() -> Result<()> {
     Ok(())
 }
 
+#[test]
+fn test_connect_fallback() -> Result<()> {
+    let _ = env_logger::try_init();
+    dotenv::from_filename(".env").ok();
+
+    if env::var("HDRS_TEST").unwrap_or_default() != "on" {
+        return Ok(());
+    }
+
+    let name_node = env::var("HDRS_NAMENODE")?;
+
+    // The first name node is dead, we should fallback to the second one.
+    let fs = ClientBuilder::new("hdfs://127.0.0.1:1")
+        .with_fallback_name_nodes(vec![name_node])
+        .connect();
+    assert!(fs.is_ok());
+
+    Ok(())
+}
+
+#[test]
+fn test_connect_force_new_instance() -> Result<()> {
+    let _ = env_logger::try_init();
+    dotenv::from_filename(".env").ok();
+
+    if env::var("HDRS_TEST").unwrap_or_default() != "on" {
+        return Ok(());
+    }
+
+    let name_node = env::var("HDRS_NAMENODE")?;
+
+    let shared = ClientBuilder::new(&name_node).connect()?;
+    {
+        let owned = ClientBuilder::new(&name_node)
+            .with_force_new_instance(true)
+            .connect()?;
+        owned.metadata("/")?;
+    }
+    // Dropping the owned instance must not close the shared one.
+    shared.metadata("/")?;
+
+    Ok(())
+}
+
+#[test]
+fn test_operation_timeout() -> Result<()> {
+    use std::time::Duration;
+
+    let _ = env_logger::try_init();
+    dotenv::from_filename(".env").ok();
+
+    if env::var("HDRS_TEST").unwrap_or_default() != "on" {
+        return Ok(());
+    }
+
+    let name_node = env::var("HDRS_NAMENODE")?;
+
+    let fs = ClientBuilder::new(&name_node)
+        .connect()?
+        .with_operation_timeout(Duration::from_secs(60));
+    assert!(fs.metadata("/")?.is_dir());
+
+    // No JNI call could finish without any wait.
+    let fs = fs.with_operation_timeout(Duration::ZERO);
+    let err = fs.metadata("/").unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::TimedOut);
+
+    Ok(())
+}
+
 #[test]
 fn test_mkdir() -> Result<()> {
     let _ = env_logger::try_init();
@@ -39,6 +110,32 @@ fn test_mkdir() -> Result<()> {
     Ok(())
 }
 
+#[test]
+fn test_statfs() -> Result<()> {
+    let _ = env_logger::try_init();
+    dotenv::from_filename(".env").ok();
+
+    if env::var("HDRS_TEST").unwrap_or_default() != "on" {
+        return Ok(());
+    }
+
+    let name_node = env::var("HDRS_NAMENODE")?;
+
+    let fs = ClientBuilder::new(&name_node).connect()?;
+
+    let stats = fs.statfs("/")?;
+    debug!("statfs: {:?}", stats);
+    assert!(stats.capacity >= stats.used);
+    assert_eq!(stats.remaining, stats.capacity - stats.used);
+
+    let err = fs
+        .statfs(&format!("/{}", uuid::Uuid::new_v4()))
+        .unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::NotFound);
+
+    Ok(())
+}
+
 #[test]
 fn test_read_dir() -> Result<()> {
     let _ = env_logger::try_init();
@@ -60,6 +157,135 @@ fn test_read_dir() -> Result<()> {
     let readdir = fs.read_dir(&path).expect("readdir should succeed");
     debug!("readdir: {:?}", readdir);
     assert_eq!(readdir.len(), 0);
+    assert!(fs.list_names(&path)?.is_empty());
+
+    fs.create_dir(&format!("{path}/dir"))?;
+    fs.write_atomic(&format!("{path}/file"), b"Hello, World!")?;
+    let mut names = fs.list_names(&path)?;
+    names.sort();
+    assert_eq!(names, ["dir", "file"]);
+
+    let filter = EntryFilter::new().with_kind(FileKind::File);
+    let paths = fs.read_dir_with(&path, filter)?.into_paths();
+    assert_eq!(paths, [format!("{path}/file")]);
+    let filter = EntryFilter::new().with_prefix("d").with_suffix("r");
+    assert_eq!(fs.read_dir_with(&path, filter)?.len(), 1);
+
+    let mut visited = 0;
+    fs.for_each_entry(&path, |meta| {
+        visited += 1;
+        assert!(meta.path().starts_with(&path));
+        Ok(())
+    })?;
+    assert_eq!(visited, 2);
+
+    let mut visited = 0;
+    let err = fs
+        .for_each_entry(&path, |_| {
+            visited += 1;
+            Err(io::Error::other("stop"))
+        })
+        .unwrap_err();
+    assert_eq!(err.to_string(), "stop");
+    assert_eq!(visited, 1);
+
+    fs.remove_dir_all(&path)?;
+
+    Ok(())
+}
+
+#[test]
+fn test_metadata_open() -> Result<()> {
+    use std::io::{Read, Write};
+
+    let _ = env_logger::try_init();
+    dotenv::from_filename(".env").ok();
+
+    if env::var("HDRS_TEST").unwrap_or_default() != "on" {
+        return Ok(());
+    }
+
+    let name_node = env::var("HDRS_NAMENODE")?;
+    let work_dir = env::var("HDRS_WORKDIR").unwrap_or_default();
+
+    let fs = ClientBuilder::new(&name_node).connect()?;
+
+    let path = format!("{work_dir}{}", uuid::Uuid::new_v4());
+    fs.create_dir(&path).expect("mkdir should succeed");
+
+    let mut f = fs
+        .open_file()
+        .create(true)
+        .write(true)
+        .open(&format!("{path}/file"))?;
+    f.write_all(b"Hello, World!")?;
+    f.flush()?;
+    drop(f);
+
+    let readdir = fs.read_dir(&path)?;
+    assert_eq!(readdir.len(), 1);
+    for meta in readdir.into_inner() {
+        let mut content = String::new();
+        meta.open(&fs)?.read_to_string(&mut content)?;
+        assert_eq!(content, "Hello, World!");
+    }
+
+    let meta = fs.metadata(&path)?;
+    assert!(meta.open(&fs).is_err());
+
+    Ok(())
+}
+
+#[test]
+fn test_read_dir_errors() -> Result<()> {
+    let _ = env_logger::try_init();
+    dotenv::from_filename(".env").ok();
+
+    if env::var("HDRS_TEST").unwrap_or_default() != "on" {
+        return Ok(());
+    }
+
+    let name_node = env::var("HDRS_NAMENODE")?;
+    let work_dir = env::var("HDRS_WORKDIR").unwrap_or_default();
+
+    let fs = ClientBuilder::new(&name_node).connect()?;
+
+    {
+        // Read dir on not exist path.
+        debug!("test read dir not found");
+        let path = format!("{work_dir}{}", uuid::Uuid::new_v4());
+        let result = fs.read_dir(&path);
+        assert_eq!(result.unwrap_err().kind(), io::ErrorKind::NotFound);
+    }
+
+    {
+        // Read dir on a file.
+        debug!("test read dir not a directory");
+        let path = format!("{work_dir}{}", uuid::Uuid::new_v4());
+        let _ = fs.open_file().create(true).write(true).open(&path)?;
+        let result = fs.read_dir(&path);
+        assert_eq!(result.unwrap_err().kind(), io::ErrorKind::NotADirectory);
+
+        // list_status returns the file itself instead.
+        let readdir = fs.list_status(&path)?;
+        assert_eq!(readdir.len(), 1);
+        fs.remove_file(&path)?;
+    }
+
+    {
+        // Read dir on a dir that contains only one file.
+        debug!("test read dir with one file");
+        let path = format!("{work_dir}{}", uuid::Uuid::new_v4());
+        fs.create_dir(&path)?;
+        let _ = fs
+            .open_file()
+            .create(true)
+            .write(true)
+            .open(&format!("{path}/file"))?;
+        let readdir = fs.read_dir(&path)?;
+        assert_eq!(readdir.len(), 1);
+        fs.remove_dir_all(&path)?;
+    }
 
     Ok(())
 }
@@ -102,6 +328,35 @@ fn test_rename() -> Result<()> {
         f.read_to_string(&mut content)?;
         assert_eq!(content.as_str(), "test file content");
     }
+    {
+        // Rename file to an existing dir.
+        let dir = format!("{work_dir}{}", uuid::Uuid::new_v4());
+        fs.create_dir(&dir)?;
+        let err = fs.rename_file(&new_path, &dir).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::IsADirectory);
+
+        // Rename dir to an existing file.
+        let err = fs.rename_file(&dir, &new_path).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::NotADirectory);
+
+        // Rename file to an existing file.
+        let other = format!("{work_dir}{}", uuid::Uuid::new_v4());
+        fs.write_atomic(&other, b"other file content")?;
+        let err = fs.rename_file(&new_path, &other).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::AlreadyExists);
+        // Both files must be untouched.
+        assert_eq!(fs.metadata(&other)?.len(), 18);
+        assert_eq!(fs.metadata(&new_path)?.len(), 17);
+        fs.remove_file(&other)?;
+
+        // Rename dir to an existing dir.
+        let other = format!("{work_dir}{}", uuid::Uuid::new_v4());
+        fs.create_dir(&other)?;
+        let err = fs.rename_file(&dir, &other).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::AlreadyExists);
+        fs.remove_dir(&other)?;
+        fs.remove_dir(&dir)?;
+    }
 
     Ok(())
 }
@@ -138,6 +393,29 @@ fn test_file() -> Result<()> {
         f.flush()?;
     }
 
+    for mode in [FlushMode::Visible, FlushMode::Durable] {
+        // Flush file with different modes
+        debug!("test file flush with {:?}", mode);
+        let path = format!("{work_dir}{}", uuid::Uuid::new_v4());
+        let mut f = fs
+            .open_file()
+            .create(true)
+            .write(true)
+            .with_flush_mode(mode)
+            .open(&path)?;
+        f.write_all(&content)?;
+        f.flush()?;
+        // Flushed data is visible to new readers before close.
+        let mut buf = Vec::new();
+        fs.open_file()
+            .read(true)
+            .open(&path)?
+            .read_to_end(&mut buf)?;
+        assert_eq!(buf, content);
+        f.flush_and_close()?;
+        fs.remove_file(&path)?;
+    }
+
     {
         // Read file
         debug!("test file read");
@@ -148,6 +426,20 @@ fn test_file() -> Result<()> {
         assert_eq!(buf, content);
     }
 
+    {
+        // Read file into multiple buffers
+        debug!("test file read vectored");
+        let mut f = fs.open_file().read(true).open(&path)?;
+        let (mut header, mut body) = ([0; 16], [0; 512]);
+        let n = f.read_vectored(&mut [
+            io::IoSliceMut::new(&mut header),
+            io::IoSliceMut::new(&mut body),
+        ])?;
+        assert!(n > 0 && n <= header.len() + body.len());
+        let buf = [&header[..], &body[..]].concat();
+        assert_eq!(buf[..n], content[..n]);
+    }
+
     {
         // Read not exist file
         debug!("test not exist file read");
@@ -159,6 +451,16 @@ fn test_file() -> Result<()> {
         assert_eq!(f.unwrap_err().kind(), io::ErrorKind::NotFound)
     }
 
+    {
+        // Read a dir
+        debug!("test dir read");
+        let dir = format!("{work_dir}{}", uuid::Uuid::new_v4());
+        fs.create_dir(&dir)?;
+        let f = fs.open_file().read(true).open(&dir);
+        assert_eq!(f.unwrap_err().kind(), io::ErrorKind::IsADirectory);
+        fs.remove_dir(&dir)?;
+    }
+
     {
         // Stat file.
         debug!("test file stat");
@@ -181,6 +483,17 @@ fn test_file() -> Result<()> {
         assert_eq!(buf, content[offset..]);
     }
 
+    {
+        // Seek before the start of file.
+        debug!("test file seek backward");
+        let mut f = fs.open_file().read(true).open(&path)?;
+        let _ = f.seek(SeekFrom::Start(10))?;
+        let result = f.seek(SeekFrom::Current(-11));
+        assert_eq!(result.unwrap_err().kind(), io::ErrorKind::InvalidInput);
+        let result = f.seek(SeekFrom::End(-(content.len() as i64) - 1));
+        assert_eq!(result.unwrap_err().kind(), io::ErrorKind::InvalidInput);
+    }
+
     {
         // Remove file
         debug!("test file remove");
@@ -199,6 +512,561 @@ fn test_file() -> Result<()> {
     Ok(())
 }
 
+#[test]
+fn test_file_write_all_large() -> Result<()> {
+    use std::io::Write;
+
+    let _ = env_logger::try_init();
+    dotenv::from_filename(".env").ok();
+
+    if env::var("HDRS_TEST").unwrap_or_default() != "on" {
+        return Ok(());
+    }
+
+    let name_node = env::var("HDRS_NAMENODE")?;
+    let work_dir = env::var("HDRS_WORKDIR").unwrap_or_default();
+
+    let fs = ClientBuilder::new(&name_node).connect()?;
+
+    let path = format!("{work_dir}{}", uuid::Uuid::new_v4());
+
+    // Larger than i32::MAX to make sure write_all never passes a truncated length.
+    let content = vec![1u8; i32::MAX as usize + 1024];
+    {
+        debug!("test file write all large");
+        let mut f = fs.open_file().create(true).write(true).open(&path)?;
+        f.write_all(&content)?;
+        f.flush()?;
+    }
+
+    let fi = fs.metadata(&path)?;
+    assert_eq!(fi.len(), content.len() as u64);
+
+    fs.remove_file(&path)?;
+
+    Ok(())
+}
+
+#[test]
+fn test_file_append_bytes() -> Result<()> {
+    use std::io::{Read, Seek, Write};
+
+    let _ = env_logger::try_init();
+    dotenv::from_filename(".env").ok();
+
+    if env::var("HDRS_TEST").unwrap_or_default() != "on" {
+        return Ok(());
+    }
+
+    let name_node = env::var("HDRS_NAMENODE")?;
+    let work_dir = env::var("HDRS_WORKDIR").unwrap_or_default();
+
+    let fs = ClientBuilder::new(&name_node).connect()?;
+
+    let path = format!("{work_dir}{}", uuid::Uuid::new_v4());
+
+    {
+        debug!("test file append bytes on write");
+        let mut f = fs.open_file().create(true).write(true).open(&path)?;
+        let err = f.read(&mut [0; 8]).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::PermissionDenied);
+        assert_eq!(f.append_bytes(b"Hello")?, 0);
+        assert_eq!(f.append_bytes(b", ")?, 5);
+        let err = f.rewind().unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::Unsupported);
+        assert_eq!(f.stream_position()?, 7);
+        f.flush_and_close()?;
+    }
+
+    {
+        debug!("test file append bytes on append");
+        let mut f = fs.open_file().append(true).open(&path)?;
+        assert_eq!(f.append_bytes(b"World")?, 7);
+        assert_eq!(f.append_bytes(b"!")?, 12);
+        f.flush_and_close()?;
+    }
+
+    {
+        debug!("test file append bytes on read");
+        let mut f = fs.open_file().read(true).open(&path)?;
+        let err = f.append_bytes(b"?").unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::PermissionDenied);
+        let err = f.write(b"?").unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::PermissionDenied);
+        // Flushing a reader is a no-op.
+        f.flush()?;
+
+        let mut content = String::new();
+        f.read_to_string(&mut content)?;
+        assert_eq!(content, "Hello, World!");
+
+        f.rewind()?;
+        content.clear();
+        f.read_to_string(&mut content)?;
+        assert_eq!(content, "Hello, World!");
+
+        assert_eq!(f.read_string_at(7, 5)?, "World");
+        let err = f.read_string_at(7, 100).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::UnexpectedEof);
+
+        let mut buf = [0; 5];
+        assert_eq!(f.seek_read(7, &mut buf)?, 5);
+        assert_eq!(&buf, b"World");
+        // The cursor is left after the bytes read, unlike read_at.
+        content.clear();
+        f.read_to_string(&mut content)?;
+        assert_eq!(content, "!");
+    }
+
+    {
+        debug!("test file max read chunk");
+        let mut f = fs
+            .open_file()
+            .read(true)
+            .with_max_read_chunk(4)
+            .open(&path)?;
+        let mut buf = [0; 16];
+        assert_eq!(f.read(&mut buf)?, 4);
+        assert_eq!(f.read_at(&mut buf, 7)?, 4);
+        assert_eq!(&buf[..4], b"Worl");
+
+        let mut content = String::new();
+        f.read_to_string(&mut content)?;
+        assert_eq!(content, "o, World!");
+    }
+
+    {
+        debug!("test open at length");
+        let mut w = fs.open_file().append(true).open(&path)?;
+        let mut r = fs.open_at_length(&path, 5)?;
+        // Appends after the checkpoint must not be visible.
+        w.append_bytes(b" Bye!")?;
+        w.flush_and_close()?;
+
+        let mut content = String::new();
+        r.read_to_string(&mut content)?;
+        assert_eq!(content, "Hello");
+
+        content.clear();
+        fs.open_at_length(&path, 100)?
+            .read_to_string(&mut content)?;
+        assert_eq!(content, "Hello, World! Bye!");
+    }
+
+    fs.remove_file(&path)?;
+
+    Ok(())
+}
+
+#[test]
+fn test_file_write_from() -> Result<()> {
+    use std::io::{Read, Write};
+
+    let _ = env_logger::try_init();
+    dotenv::from_filename(".env").ok();
+
+    if env::var("HDRS_TEST").unwrap_or_default() != "on" {
+        return Ok(());
+    }
+
+    let name_node = env::var("HDRS_NAMENODE")?;
+    let work_dir = env::var("HDRS_WORKDIR").unwrap_or_default();
+
+    let fs = ClientBuilder::new(&name_node).connect()?;
+
+    let path = format!("{work_dir}{}", uuid::Uuid::new_v4());
+    let content: Vec<u8> = (0..3 * 1024 * 1024).map(|i| (i % 251) as u8).collect();
+
+    let mut f = fs.open_file().create(true).write(true).open(&path)?;
+    let n = f.write_from(&mut content.as_slice())?;
+    assert_eq!(n, content.len() as u64);
+    f.flush_and_close()?;
+
+    let mut buf = Vec::new();
+    fs.open_file()
+        .read(true)
+        .open(&path)?
+        .read_to_end(&mut buf)?;
+    assert_eq!(buf, content);
+
+    debug!("test file write all from slices");
+    let mut f = fs
+        .open_file()
+        .create(true)
+        .write(true)
+        .truncate(true)
+        .open(&path)?;
+    let slices: Vec<&[u8]> = vec![&content[..7], &[], &content[7..1024], &content[1024..]];
+    f.write_all_from(&slices)?;
+    f.flush_and_close()?;
+    buf.clear();
+    fs.open_file()
+        .read(true)
+        .open(&path)?
+        .read_to_end(&mut buf)?;
+    assert_eq!(buf, content);
+
+    debug!("test file sync on write");
+    let mut f = fs
+        .open_file()
+        .create(true)
+        .write(true)
+        .truncate(true)
+        .sync_on_write(true)
+        .open(&path)?;
+    f.write_all(b"Hello, World!")?;
+    // Synced data must be visible to new readers before close.
+    let mut r = fs.open_file().read(true).open(&path)?;
+    let err = r.sync_all().unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::PermissionDenied);
+    buf.clear();
+    r.read_to_end(&mut buf)?;
+    assert_eq!(buf, b"Hello, World!");
+    f.sync_all()?;
+    f.flush_and_close()?;
+
+    fs.remove_file(&path)?;
+
+    Ok(())
+}
+
+#[test]
+fn test_write_atomic() -> Result<()> {
+    use std::io::Read;
+
+    let _ = env_logger::try_init();
+    dotenv::from_filename(".env").ok();
+
+    if env::var("HDRS_TEST").unwrap_or_default() != "on" {
+        return Ok(());
+    }
+
+    let name_node = env::var("HDRS_NAMENODE")?;
+    let work_dir = env::var("HDRS_WORKDIR").unwrap_or_default();
+
+    let fs = ClientBuilder::new(&name_node).connect()?;
+
+    let dir = format!("{work_dir}{}", uuid::Uuid::new_v4());
+    let path = format!("{dir}/a/b/file");
+
+    for content in ["Hello, World!", "Hello, hdrs!"] {
+        debug!("test write atomic {}", content);
+        fs.write_atomic(&path, content.as_bytes())?;
+
+        let mut buf = String::new();
+        fs.open_file()
+            .read(true)
+            .open(&path)?
+            .read_to_string(&mut buf)?;
+        assert_eq!(buf, content);
+    }
+
+    // Temp files must be cleaned up.
+    assert_eq!(fs.read_dir(&format!("{dir}/a/b"))?.len(), 1);
+
+    fs.remove_dir_all(&dir)?;
+
+    Ok(())
+}
+
+#[test]
+fn test_rename_file_overwrite() -> Result<()> {
+    use std::io::Read;
+
+    let _ = env_logger::try_init();
+    dotenv::from_filename(".env").ok();
+
+    if env::var("HDRS_TEST").unwrap_or_default() != "on" {
+        return Ok(());
+    }
+
+    let name_node = env::var("HDRS_NAMENODE")?;
+    let work_dir = env::var("HDRS_WORKDIR").unwrap_or_default();
+
+    let fs = ClientBuilder::new(&name_node).connect()?;
+
+    let dir = format!("{work_dir}{}", uuid::Uuid::new_v4());
+    let (src, dst) = (format!("{dir}/src"), format!("{dir}/dst"));
+    fs.write_atomic(&dst, b"old")?;
+
+    // Missing source must not touch the destination.
+    let err = fs.rename_file_overwrite(&src, &dst).unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::NotFound);
+    assert_eq!(fs.metadata(&dst)?.len(), 3);
+
+    // Dir destination is rejected.
+    fs.write_atomic(&src, b"new content")?;
+    fs.create_dir(&format!("{dir}/sub"))?;
+    let err = fs
+        .rename_file_overwrite(&src, &format!("{dir}/sub"))
+        .unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::IsADirectory);
+
+    let replaced = fs.rename_file_overwrite(&src, &dst)?;
+    assert_eq!(replaced.map(|m| m.len()), Some(3));
+    let mut content = String::new();
+    fs.open_file()
+        .read(true)
+        .open(&dst)?
+        .read_to_string(&mut content)?;
+    assert_eq!(content, "new content");
+    // The overwritten file must be cleaned up.
+    let mut names = fs.list_names(&dir)?;
+    names.sort();
+    assert_eq!(names, ["dst", "sub"]);
+
+    fs.remove_dir_all(&dir)?;
+
+    Ok(())
+}
+
+#[test]
+fn test_set_permission() -> Result<()> {
+    let _ = env_logger::try_init();
+    dotenv::from_filename(".env").ok();
+
+    if env::var("HDRS_TEST").unwrap_or_default() != "on" {
+        return Ok(());
+    }
+
+    let name_node = env::var("HDRS_NAMENODE")?;
+    let work_dir = env::var("HDRS_WORKDIR").unwrap_or_default();
+
+    let fs = ClientBuilder::new(&name_node).connect()?;
+
+    let path = format!("{work_dir}{}", uuid::Uuid::new_v4());
+    fs.write_atomic(&path, b"Hello, World!")?;
+
+    fs.set_permission(&path, 0o640)?;
+    assert_eq!(fs.metadata(&path)?.permissions(), 0o640);
+
+    let err = fs.set_permission(&path, 0o10000).unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+    assert_eq!(fs.metadata(&path)?.permissions(), 0o640);
+
+    fs.remove_file(&path)?;
+
+    Ok(())
+}
+
+#[test]
+fn test_set_permission_recursive() -> Result<()> {
+    let _ = env_logger::try_init();
+    dotenv::from_filename(".env").ok();
+
+    if env::var("HDRS_TEST").unwrap_or_default() != "on" {
+        return Ok(());
+    }
+
+    let name_node = env::var("HDRS_NAMENODE")?;
+    let work_dir = env::var("HDRS_WORKDIR").unwrap_or_default();
+
+    let fs = ClientBuilder::new(&name_node).connect()?;
+
+    let dir = format!("{work_dir}{}", uuid::Uuid::new_v4());
+    fs.write_atomic(&format!("{dir}/a/b/file"), b"Hello, World!")?;
+    fs.write_atomic(&format!("{dir}/file"), b"Hello, World!")?;
+
+    fs.set_permission_recursive_split(&dir, 0o750, 0o640)?;
+    for (path, mode) in [
+        (dir.clone(), 0o750),
+        (format!("{dir}/a"), 0o750),
+        (format!("{dir}/a/b"), 0o750),
+        (format!("{dir}/a/b/file"), 0o640),
+        (format!("{dir}/file"), 0o640),
+    ] {
+        assert_eq!(fs.metadata(&path)?.permissions(), mode, "mode of {path}");
+    }
+
+    let err = fs
+        .set_permission_recursive(&format!("{dir}/not-exist"), 0o755)
+        .unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::NotFound);
+
+    fs.remove_dir_all(&dir)?;
+
+    Ok(())
+}
+
+#[test]
+fn test_copy_preserve() -> Result<()> {
+    use std::io::{Read, Write};
+
+    let _ = env_logger::try_init();
+    dotenv::from_filename(".env").ok();
+
+    if env::var("HDRS_TEST").unwrap_or_default() != "on" {
+        return Ok(());
+    }
+
+    let name_node = env::var("HDRS_NAMENODE")?;
+    let work_dir = env::var("HDRS_WORKDIR").unwrap_or_default();
+
+    let fs = ClientBuilder::new(&name_node).connect()?;
+
+    let dir = format!("{work_dir}{}", uuid::Uuid::new_v4());
+    let src = format!("{dir}/src");
+    let dst = format!("{dir}/dst");
+    let mut f = fs
+        .open_file()
+        .create(true)
+        .write(true)
+        .with_replication(1)
+        .open(&src)?;
+    f.write_all(b"Hello, World!")?;
+    f.flush_and_close()?;
+    fs.set_permission(&src, 0o600)?;
+    // Make sure the times of dst will differ if they are not preserved.
+    std::thread::sleep(Duration::from_millis(1100));
+
+    let failed = fs.copy_preserve(&src, &dst)?;
+    // Owner could fail to be preserved if we are not superuser.
+    assert!(
+        failed.iter().all(|(attr, _)| *attr == "owner"),
+        "attributes not preserved: {failed:?}"
+    );
+
+    let mut content = String::new();
+    fs.open_file()
+        .read(true)
+        .open(&dst)?
+        .read_to_string(&mut content)?;
+    assert_eq!(content, "Hello, World!");
+
+    let (src, dst) = (fs.metadata(&src)?, fs.metadata(&dst)?);
+    assert_eq!(dst.permissions(), 0o600);
+    assert_eq!(dst.replication(), 1);
+    assert_eq!(dst.modified(), src.modified());
+
+    fs.remove_dir_all(&dir)?;
+
+    Ok(())
+}
+
+#[test]
+fn test_can_write() -> Result<()> {
+    let _ = env_logger::try_init();
+    dotenv::from_filename(".env").ok();
+
+    if env::var("HDRS_TEST").unwrap_or_default() != "on" {
+        return Ok(());
+    }
+
+    let name_node = env::var("HDRS_NAMENODE")?;
+    let work_dir = env::var("HDRS_WORKDIR").unwrap_or_default();
+
+    let fs = ClientBuilder::new(&name_node).connect()?;
+
+    let dir = format!("{work_dir}{}", uuid::Uuid::new_v4());
+    fs.create_dir(&dir)?;
+
+    assert!(fs.can_write(&dir)?);
+    // Probe file must be removed.
+    assert!(fs.read_dir(&dir)?.is_empty());
+
+    let err = fs.can_write(&format!("{dir}/not-exist")).unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::NotFound);
+    // Parent dirs must not be created by the probe.
+    assert!(fs.read_dir(&dir)?.is_empty());
+
+    fs.remove_dir_all(&dir)?;
+
+    Ok(())
+}
+
+#[test]
+fn test_remove_if_exists() -> Result<()> {
+    let _ = env_logger::try_init();
+    dotenv::from_filename(".env").ok();
+
+    if env::var("HDRS_TEST").unwrap_or_default() != "on" {
+        return Ok(());
+    }
+
+    let name_node = env::var("HDRS_NAMENODE")?;
+    let work_dir = env::var("HDRS_WORKDIR").unwrap_or_default();
+
+    let fs = ClientBuilder::new(&name_node).connect()?;
+
+    let dir = format!("{work_dir}{}", uuid::Uuid::new_v4());
+    let path = format!("{dir}/file");
+    fs.write_atomic(&path, b"Hello, World!")?;
+
+    assert!(fs.remove_file_if_exists(&path)?);
+    assert!(!fs.remove_file_if_exists(&path)?);
+
+    assert!(fs.remove_dir_all_if_exists(&dir)?);
+    assert!(!fs.remove_dir_all_if_exists(&dir)?);
+
+    fs.write_atomic(&format!("{dir}/a/file"), b"Hello, World!")?;
+    fs.write_atomic(&format!("{dir}/b/file"), b"Hello, World!")?;
+    let (a, b) = (format!("{dir}/a"), format!("{dir}/b"));
+
+    let results = fs.remove_many(&[&a, &b], false);
+    assert_eq!(results.len(), 2);
+    assert!(results.iter().all(|(_, res)| res.is_err()));
+
+    let missing = format!("{dir}/missing");
+    let results = fs.remove_many(&[&a, &missing, &b], true);
+    assert_eq!(results[0].0, a);
+    assert!(results[0].1.is_ok());
+    assert!(results[1].1.is_err());
+    assert!(results[2].1.is_ok());
+
+    fs.remove_dir_all(&dir)?;
+
+    Ok(())
+}
+
+#[cfg(feature = "async_file")]
+#[tokio::test]
+async fn test_read_dir_prefetch() -> Result<()> {
+    use std::num::NonZeroUsize;
+
+    use futures::StreamExt;
+
+    let _ = env_logger::try_init();
+    dotenv::from_filename(".env").ok();
+
+    if env::var("HDRS_TEST").unwrap_or_default() != "on" {
+        return Ok(());
+    }
+
+    let name_node = env::var("HDRS_NAMENODE")?;
+    let work_dir = env::var("HDRS_WORKDIR").unwrap_or_default();
+
+    let fs = ClientBuilder::new(&name_node).connect()?;
+
+    let path = format!("{work_dir}{}", uuid::Uuid::new_v4());
+    fs.create_dir(&format!("{path}/dir/sub"))?;
+    fs.open_file()
+        .create(true)
+        .write(true)
+        .open(&format!("{path}/file"))?;
+
+    let owned = ClientBuilder::new(&name_node)
+        .with_force_new_instance(true)
+        .connect()?;
+    let stream = owned
+        .read_dir_prefetch(&path, NonZeroUsize::new(2).unwrap())
+        .await?;
+    // The stream must keep working after the owned client is dropped.
+    drop(owned);
+    let entries: Vec<_> = stream.collect().await;
+    assert_eq!(entries.len(), 2);
+    for (meta, children) in entries {
+        match meta.file_name() {
+            "dir" => assert_eq!(children.expect("must be prefetched")?.len(), 1),
+            "file" => assert!(children.is_none()),
+            v => panic!("unexpected entry {v}"),
+        }
+    }
+
+    fs.remove_dir_all(&path)?;
+
+    Ok(())
+}
+
 #[cfg(feature = "async_file")]
 #[tokio::test]
 async fn test_tokio_file() -> Result<()> {
@@ -237,6 +1105,36 @@ async fn test_tokio_file() -> Result<()> {
         f.flush().await?;
     }
 
+    for mode in [FlushMode::Visible, FlushMode::Durable] {
+        debug!("test file flush with {:?}", mode);
+        let path = format!("{path}.{mode:?}");
+        let mut f = fs
+            .open_file()
+            .create(true)
+            .write(true)
+            .with_flush_mode(mode)
+            .async_open(&path)
+            .await?;
+        assert_eq!(f.flush_mode(), mode);
+        // Nothing written, flush should be skipped.
+        f.flush().await?;
+        f.write_all(&content).await?;
+        f.flush().await?;
+
+        // Flushed data must be visible to new readers before close.
+        let mut buf = Vec::new();
+        fs.open_file()
+            .read(true)
+            .async_open(&path)
+            .await?
+            .read_to_end(&mut buf)
+            .await?;
+        assert_eq!(buf, content);
+
+        f.close().await?;
+        fs.remove_file(&path)?;
+    }
+
     {
         // Read file
         debug!("test file read");
@@ -395,6 +1293,8 @@ fn test_client_with_user() -> Result<()> {
     let meta = fs.metadata(&test_dir);
     assert!(meta.is_ok());
     assert_eq!(meta.unwrap().owner(), "test_user");
+    assert_eq!(fs.effective_user()?, "test_user");
+    assert_eq!(fs.effective_user_in(&test_dir)?, "test_user");
 
     Ok(())
 }